@@ -0,0 +1,429 @@
+//! Reads ALICA messages straight from the unpacked Cap'n Proto wire format capnzero sends.
+
+use std::cell::Cell;
+use serde_json::{Map, Value};
+
+const MAX_NESTING_DEPTH: usize = 32;
+const TRAVERSAL_LIMIT_FACTOR: usize = 8;
+
+enum Kind {
+    Bool(usize),
+    UInt8(usize),
+    Int64(usize),
+    Text(usize),
+    Data(usize),
+    Struct(usize, &'static [Field]),
+    Int64List(usize),
+    StructList(usize, &'static [Field])
+}
+
+struct Field {
+    name: &'static str,
+    kind: Kind
+}
+
+/// Fields of the root struct of a message type, see `alica::MessageTypeRegistry`.
+pub struct Layout {
+    fields: &'static [Field]
+}
+
+impl Layout {
+    pub fn field_names(&self) -> Vec<&'static str> {
+        self.fields.iter().map(|field| field.name).collect()
+    }
+}
+
+const ID: &[Field] = &[
+    Field { name: "type", kind: Kind::UInt8(0) },
+    Field { name: "value", kind: Kind::Data(0) }
+];
+
+pub const ALICA_ENGINE_INFO: Layout = Layout { fields: &[
+    Field { name: "senderId", kind: Kind::Struct(0, ID) },
+    Field { name: "masterPlan", kind: Kind::Text(1) },
+    Field { name: "currentPlan", kind: Kind::Text(2) },
+    Field { name: "currentState", kind: Kind::Text(3) },
+    Field { name: "currentRole", kind: Kind::Text(4) },
+    Field { name: "currentTask", kind: Kind::Text(5) },
+    Field { name: "agentIdsWithMe", kind: Kind::StructList(6, ID) }
+] };
+
+pub const PLAN_TREE_INFO: Layout = Layout { fields: &[
+    Field { name: "senderId", kind: Kind::Struct(0, ID) },
+    Field { name: "stateIds", kind: Kind::Int64List(1) },
+    Field { name: "succeededEps", kind: Kind::Int64List(2) }
+] };
+
+const SYNC_DATA: &[Field] = &[
+    Field { name: "robotId", kind: Kind::Struct(0, ID) },
+    Field { name: "transitionId", kind: Kind::Int64(0) },
+    Field { name: "transitionHolds", kind: Kind::Bool(64) },
+    Field { name: "ack", kind: Kind::Bool(65) }
+];
+
+pub const SYNC_TALK: Layout = Layout { fields: &[
+    Field { name: "senderId", kind: Kind::Struct(0, ID) },
+    Field { name: "syncData", kind: Kind::StructList(1, SYNC_DATA) }
+] };
+
+pub const SYNC_READY: Layout = Layout { fields: &[
+    Field { name: "senderId", kind: Kind::Struct(0, ID) },
+    Field { name: "synchronisationId", kind: Kind::Int64(0) }
+] };
+
+const ENTRYPOINT_ROBOTS: &[Field] = &[
+    Field { name: "entrypoint", kind: Kind::Int64(0) },
+    Field { name: "robots", kind: Kind::StructList(0, ID) }
+];
+
+pub const ALLOCATION_AUTHORITY_INFO: Layout = Layout { fields: &[
+    Field { name: "senderId", kind: Kind::Struct(0, ID) },
+    Field { name: "planId", kind: Kind::Int64(0) },
+    Field { name: "parentState", kind: Kind::Int64(1) },
+    Field { name: "planType", kind: Kind::Int64(2) },
+    Field { name: "authority", kind: Kind::Struct(1, ID) },
+    Field { name: "entrypointRobots", kind: Kind::StructList(2, ENTRYPOINT_ROBOTS) }
+] };
+
+pub const ROLE_SWITCH: Layout = Layout { fields: &[
+    Field { name: "senderId", kind: Kind::Struct(0, ID) },
+    Field { name: "roleId", kind: Kind::Int64(0) }
+] };
+
+const SOLVER_VAR: &[Field] = &[
+    Field { name: "id", kind: Kind::Int64(0) },
+    Field { name: "value", kind: Kind::Data(0) }
+];
+
+pub const SOLVER_RESULT: Layout = Layout { fields: &[
+    Field { name: "senderId", kind: Kind::Struct(0, ID) },
+    Field { name: "vars", kind: Kind::StructList(1, SOLVER_VAR) }
+] };
+
+/// Returns `None` if the bytes are not a well formed Cap'n Proto message.
+pub fn decode(layout: &Layout, bytes: &[u8]) -> Option<Value> {
+    let message = Message::parse(bytes)?;
+    let root = message.read_struct(0, 0, 0)?;
+    message.decode_struct(&root, layout.fields, 0)
+}
+
+/// Extracts the id of the sending agent as hex string from a decoded message.
+pub fn sender_id_of(decoded_message: &Value) -> Option<String> {
+    decoded_message.get("senderId")
+        .and_then(|sender_id| sender_id.get("value"))
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+struct StructReader {
+    segment: usize,
+    data_start: usize,
+    data_words: usize,
+    pointers_start: usize,
+    pointer_count: usize
+}
+
+struct ListReader {
+    segment: usize,
+    start: usize,
+    element_size: u64,
+    element_count: usize,
+    struct_data_words: usize,
+    struct_pointer_count: usize
+}
+
+struct Message<'a> {
+    segments: Vec<&'a [u8]>,
+    traversal_budget: Cell<usize>
+}
+
+impl<'a> Message<'a> {
+    fn parse(bytes: &'a [u8]) -> Option<Self> {
+        let segment_count = read_u32(bytes, 0)? as usize + 1;
+        if segment_count > 512 {
+            return None;
+        }
+
+        let table_words = (4 + 4 * segment_count + 7) / 8;
+        let mut offset = table_words * 8;
+        let mut segments = Vec::new();
+        for index in 0..segment_count {
+            let size = read_u32(bytes, 4 + 4 * index)? as usize * 8;
+            segments.push(bytes.get(offset..offset.checked_add(size)?)?);
+            offset += size;
+        }
+
+        if offset != bytes.len() {
+            return None;
+        }
+        let traversal_budget = Cell::new(TRAVERSAL_LIMIT_FACTOR * (bytes.len() / 8));
+        Some(Message { segments, traversal_budget })
+    }
+
+    /// Counts words against the traversal budget, lists of empty structs count one word per element.
+    fn traverse(&self, words: usize) -> Option<()> {
+        let remaining = self.traversal_budget.get().checked_sub(words)?;
+        self.traversal_budget.set(remaining);
+        Some(())
+    }
+
+    fn word(&self, segment: usize, index: usize) -> Option<u64> {
+        let bytes = self.segments.get(segment)?.get(index * 8..index * 8 + 8)?;
+        let mut word = [0u8; 8];
+        word.copy_from_slice(bytes);
+        Some(u64::from_le_bytes(word))
+    }
+
+    /// Follows far pointers to the segment, word index and pointer word of the target.
+    fn resolve(&self, segment: usize, index: usize, depth: usize) -> Option<(usize, usize, u64)> {
+        let pointer = self.word(segment, index)?;
+        if pointer & 3 != 2 {
+            let offset = ((pointer as u32 as i32) >> 2) as isize;
+            let target = (index as isize).checked_add(1 + offset)?;
+            return if target < 0 { None } else { Some((segment, target as usize, pointer)) };
+        }
+
+        if depth > 0 {
+            return None;
+        }
+        let landing_segment = (pointer >> 32) as usize;
+        let landing_pad = ((pointer as u32) >> 3) as usize;
+        if pointer & 4 == 0 {
+            self.resolve(landing_segment, landing_pad, depth + 1)
+        } else {
+            let far_pointer = self.word(landing_segment, landing_pad)?;
+            let tag = self.word(landing_segment, landing_pad + 1)?;
+            if far_pointer & 7 != 2 {
+                return None;
+            }
+            Some(((far_pointer >> 32) as usize, ((far_pointer as u32) >> 3) as usize, tag))
+        }
+    }
+
+    fn read_struct(&self, segment: usize, index: usize, depth: usize) -> Option<StructReader> {
+        if depth > MAX_NESTING_DEPTH {
+            return None;
+        }
+        let (segment, target, pointer) = self.resolve(segment, index, 0)?;
+        if pointer & 3 != 0 {
+            return None;
+        }
+
+        let data_words = ((pointer >> 32) & 0xffff) as usize;
+        let pointer_count = (pointer >> 48) as usize;
+        self.segments.get(segment)?.get(target * 8..(target + data_words + pointer_count) * 8)?;
+        self.traverse((data_words + pointer_count).max(1))?;
+
+        Some(StructReader {
+            segment,
+            data_start: target,
+            data_words,
+            pointers_start: target + data_words,
+            pointer_count
+        })
+    }
+
+    fn read_list(&self, segment: usize, index: usize) -> Option<ListReader> {
+        let (segment, target, pointer) = self.resolve(segment, index, 0)?;
+        if pointer & 3 != 1 {
+            return None;
+        }
+
+        let element_size = (pointer >> 32) & 7;
+        let count = (pointer >> 35) as usize;
+        if element_size != 7 {
+            let bits_per_element = [0, 1, 8, 16, 32, 64, 64][element_size as usize];
+            let words = (count * bits_per_element + 63) / 64;
+            self.segments.get(segment)?.get(target * 8..(target + words) * 8)?;
+            self.traverse(words.max(1))?;
+            return Some(ListReader {
+                segment,
+                start: target,
+                element_size,
+                element_count: count,
+                struct_data_words: 0,
+                struct_pointer_count: 0
+            });
+        }
+
+        let tag = self.word(segment, target)?;
+        let element_count = ((tag as u32) >> 2) as usize;
+        let struct_data_words = ((tag >> 32) & 0xffff) as usize;
+        let struct_pointer_count = (tag >> 48) as usize;
+        if element_count * (struct_data_words + struct_pointer_count) > count {
+            return None;
+        }
+        self.segments.get(segment)?.get(target * 8..(target + 1 + count) * 8)?;
+        self.traverse(count.max(element_count).max(1))?;
+
+        Some(ListReader {
+            segment,
+            start: target + 1,
+            element_size,
+            element_count,
+            struct_data_words,
+            struct_pointer_count
+        })
+    }
+
+    fn read_bytes(&self, reader: &StructReader, pointer: usize) -> Option<Option<&'a [u8]>> {
+        if pointer >= reader.pointer_count || self.word(reader.segment, reader.pointers_start + pointer)? == 0 {
+            return Some(None);
+        }
+        let list = self.read_list(reader.segment, reader.pointers_start + pointer)?;
+        if list.element_size != 2 {
+            return None;
+        }
+        let segment: &'a [u8] = self.segments.get(list.segment)?;
+        Some(Some(segment.get(list.start * 8..list.start * 8 + list.element_count)?))
+    }
+
+    fn data_bytes(&self, reader: &StructReader, offset: usize, length: usize) -> Option<u64> {
+        if offset + length > reader.data_words * 8 {
+            return Some(0);
+        }
+        let segment = self.segments.get(reader.segment)?;
+        let start = reader.data_start * 8 + offset;
+        let mut value = [0u8; 8];
+        value[..length].copy_from_slice(segment.get(start..start + length)?);
+        Some(u64::from_le_bytes(value))
+    }
+
+    fn decode_struct(&self, reader: &StructReader, fields: &[Field], depth: usize) -> Option<Value> {
+        let mut decoded = Map::new();
+        for field in fields {
+            decoded.insert(field.name.to_string(), self.decode_field(reader, &field.kind, depth)?);
+        }
+        Some(Value::Object(decoded))
+    }
+
+    fn decode_field(&self, reader: &StructReader, kind: &Kind, depth: usize) -> Option<Value> {
+        match *kind {
+            Kind::Bool(bit) => Some(Value::from(self.data_bytes(reader, bit / 8, 1)? & (1 << (bit % 8)) != 0)),
+            Kind::UInt8(offset) => Some(Value::from(self.data_bytes(reader, offset, 1)?)),
+            Kind::Int64(index) => Some(Value::from(self.data_bytes(reader, index * 8, 8)? as i64)),
+            Kind::Text(pointer) => Some(match self.read_bytes(reader, pointer)? {
+                Some(text) => Value::from(String::from_utf8_lossy(text.strip_suffix(&[0]).unwrap_or(text)).to_string()),
+                None => Value::from("")
+            }),
+            Kind::Data(pointer) => Some(match self.read_bytes(reader, pointer)? {
+                Some(data) => Value::from(data_encoding::HEXLOWER.encode(data)),
+                None => Value::from("")
+            }),
+            Kind::Struct(pointer, fields) => {
+                if pointer >= reader.pointer_count || self.word(reader.segment, reader.pointers_start + pointer)? == 0 {
+                    return Some(Value::Null);
+                }
+                let child = self.read_struct(reader.segment, reader.pointers_start + pointer, depth + 1)?;
+                self.decode_struct(&child, fields, depth + 1)
+            },
+            Kind::Int64List(pointer) => {
+                if pointer >= reader.pointer_count || self.word(reader.segment, reader.pointers_start + pointer)? == 0 {
+                    return Some(Value::Array(Vec::new()));
+                }
+                let list = self.read_list(reader.segment, reader.pointers_start + pointer)?;
+                if list.element_size != 5 {
+                    return None;
+                }
+                (0..list.element_count)
+                    .map(|index| self.word(list.segment, list.start + index).map(|value| Value::from(value as i64)))
+                    .collect::<Option<Vec<Value>>>()
+                    .map(Value::Array)
+            },
+            Kind::StructList(pointer, fields) => {
+                if pointer >= reader.pointer_count || self.word(reader.segment, reader.pointers_start + pointer)? == 0 {
+                    return Some(Value::Array(Vec::new()));
+                }
+                if depth >= MAX_NESTING_DEPTH {
+                    return None;
+                }
+                let list = self.read_list(reader.segment, reader.pointers_start + pointer)?;
+                if list.element_size != 7 {
+                    return None;
+                }
+                let element_words = list.struct_data_words + list.struct_pointer_count;
+                (0..list.element_count)
+                    .map(|index| {
+                        let element = StructReader {
+                            segment: list.segment,
+                            data_start: list.start + index * element_words,
+                            data_words: list.struct_data_words,
+                            pointers_start: list.start + index * element_words + list.struct_data_words,
+                            pointer_count: list.struct_pointer_count
+                        };
+                        self.decode_struct(&element, fields, depth + 1)
+                    })
+                    .collect::<Option<Vec<Value>>>()
+                    .map(Value::Array)
+            }
+        }
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let mut value = [0u8; 4];
+    value.copy_from_slice(bytes.get(offset..offset + 4)?);
+    Some(u32::from_le_bytes(value))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::alica::capnp;
+
+    fn role_switch_message() -> Vec<u8> {
+        let mut message = Vec::new();
+        // segment table: one segment of 6 words
+        message.extend_from_slice(&[0, 0, 0, 0, 6, 0, 0, 0]);
+        // root pointer: struct at offset 0, 1 data word, 1 pointer
+        message.extend_from_slice(&[0, 0, 0, 0, 1, 0, 1, 0]);
+        // RoleSwitch.roleId
+        message.extend_from_slice(&42i64.to_le_bytes());
+        // RoleSwitch.senderId: struct at offset 0, 1 data word, 1 pointer
+        message.extend_from_slice(&[0, 0, 0, 0, 1, 0, 1, 0]);
+        // ID.type
+        message.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 0]);
+        // ID.value: byte list at offset 0 with 2 elements
+        message.extend_from_slice(&[1, 0, 0, 0, 2 << 3 | 2, 0, 0, 0]);
+        message.extend_from_slice(&[0xab, 0xcd, 0, 0, 0, 0, 0, 0]);
+        message
+    }
+
+    #[test]
+    fn it_decodes_a_role_switch_message() {
+        let decoded_message = capnp::decode(&capnp::ROLE_SWITCH, &role_switch_message()).expect("Message was not decoded");
+
+        assert_eq!(decoded_message["roleId"], 42);
+        assert_eq!(decoded_message["senderId"]["type"], 1);
+        assert_eq!(capnp::sender_id_of(&decoded_message), Some("abcd".to_string()));
+    }
+
+    #[test]
+    fn it_rejects_truncated_messages() {
+        let message = role_switch_message();
+
+        assert!(capnp::decode(&capnp::ROLE_SWITCH, &message[..message.len() - 8]).is_none());
+    }
+
+    #[test]
+    fn it_stops_decoding_lists_beyond_the_traversal_limit() {
+        let mut message = Vec::new();
+        // segment table: one segment of 4 words
+        message.extend_from_slice(&[0, 0, 0, 0, 4, 0, 0, 0]);
+        // root pointer: struct at offset 0, no data words, 2 pointers
+        message.extend_from_slice(&[0, 0, 0, 0, 0, 0, 2, 0]);
+        // SyncTalk.senderId
+        message.extend_from_slice(&[0; 8]);
+        // SyncTalk.syncData: composite list at offset 0 without content words
+        message.extend_from_slice(&[1, 0, 0, 0, 7, 0, 0, 0]);
+        // list tag: 2^29 elements of empty structs
+        message.extend_from_slice(&(1u32 << 31).to_le_bytes());
+        message.extend_from_slice(&[0; 4]);
+
+        assert!(capnp::decode(&capnp::SYNC_TALK, &message).is_none());
+    }
+
+    #[test]
+    fn it_does_not_decode_text_messages() {
+        assert!(capnp::decode(&capnp::ROLE_SWITCH, "{\"roleId\": 42}".as_bytes()).is_none());
+    }
+}
@@ -0,0 +1,76 @@
+//! ZeroMQ sockets that send and receive a topic frame followed by the unpacked Cap'n Proto message.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Sends messages on a topic to the subscribers of an endpoint.
+pub trait Publisher {
+    fn publish(&mut self, endpoint: &str, topic: &str, message: &[u8]) -> Result<(), zmq::Error>;
+}
+
+/// Publishes through a PUB socket per endpoint, which is bound so that ALICA agents can connect.
+pub struct ZmqPublisher {
+    sockets: BTreeMap<String, zmq::Socket>
+}
+
+impl ZmqPublisher {
+    pub fn bind(endpoints: &[&str]) -> Result<Self, zmq::Error> {
+        let context = zmq::Context::new();
+        let mut sockets = BTreeMap::new();
+        for endpoint in endpoints {
+            let socket = context.socket(zmq::PUB)?;
+            socket.bind(endpoint)?;
+            sockets.insert(endpoint.to_string(), socket);
+        }
+        Ok(ZmqPublisher { sockets })
+    }
+}
+
+impl Publisher for ZmqPublisher {
+    fn publish(&mut self, endpoint: &str, topic: &str, message: &[u8]) -> Result<(), zmq::Error> {
+        let socket = self.sockets.get(endpoint).ok_or(zmq::Error::EINVAL)?;
+        socket.send(topic.as_bytes(), zmq::SNDMORE)?;
+        socket.send(message, 0)
+    }
+}
+
+/// Receives the messages published on the endpoints it is connected to.
+pub trait Subscriber {
+    /// Returns the topic and message that arrived next or `None` if nothing arrived within the timeout.
+    fn receive(&mut self, timeout: Duration) -> Result<Option<(String, Vec<u8>)>, zmq::Error>;
+}
+
+/// Receives through a SUB socket that connects to the PUB sockets the ALICA agents bind.
+pub struct ZmqSubscriber {
+    socket: zmq::Socket
+}
+
+impl ZmqSubscriber {
+    /// Subscribes to every topic on all endpoints.
+    pub fn connect(endpoints: &[&str]) -> Result<Self, zmq::Error> {
+        let context = zmq::Context::new();
+        let socket = context.socket(zmq::SUB)?;
+        for endpoint in endpoints {
+            socket.connect(endpoint)?;
+        }
+        socket.set_subscribe(b"")?;
+        Ok(ZmqSubscriber { socket })
+    }
+}
+
+impl Subscriber for ZmqSubscriber {
+    fn receive(&mut self, timeout: Duration) -> Result<Option<(String, Vec<u8>)>, zmq::Error> {
+        if self.socket.poll(zmq::POLLIN, timeout.as_millis() as i64)? == 0 {
+            return Ok(None);
+        }
+
+        let mut frames = self.socket.recv_multipart(0)?;
+        if frames.len() != 2 {
+            // Publishers that do not speak capnzero, nothing can be recorded from their messages.
+            return Ok(None);
+        }
+        let message = frames.pop().unwrap_or_default();
+        let topic = String::from_utf8_lossy(&frames[0]).to_string();
+        Ok(Some((topic, message)))
+    }
+}
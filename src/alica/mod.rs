@@ -0,0 +1,132 @@
+pub mod capnp;
+pub mod capnzero;
+
+use serde_json::Value;
+
+static STANDARD_MESSAGE_TYPES: [(&str, capnp::Layout); 7] = [
+    ("AlicaEngineInfo", capnp::ALICA_ENGINE_INFO),
+    ("PlanTreeInfo", capnp::PLAN_TREE_INFO),
+    ("SyncTalk", capnp::SYNC_TALK),
+    ("SyncReady", capnp::SYNC_READY),
+    ("AllocationAuthorityInfo", capnp::ALLOCATION_AUTHORITY_INFO),
+    ("RoleSwitch", capnp::ROLE_SWITCH),
+    ("SolverResult", capnp::SOLVER_RESULT)
+];
+
+pub trait MessageSchema {
+    fn validate(&self, message: &[u8]) -> Result<(), String>;
+}
+
+/// Requires top level fields in JSON messages, binary messages are not checked.
+pub struct JsonFieldSchema {
+    required_fields: Vec<String>
+}
+
+impl JsonFieldSchema {
+    pub fn new(required_fields: &[&str]) -> Self {
+        JsonFieldSchema {
+            required_fields: required_fields.iter().map(|field| field.to_string()).collect()
+        }
+    }
+}
+
+impl MessageSchema for JsonFieldSchema {
+    fn validate(&self, message: &[u8]) -> Result<(), String> {
+        let fields = match serde_json::from_slice::<Value>(message) {
+            Ok(Value::Object(fields)) => fields,
+            Ok(_) => return Err("is JSON but not an object".to_string()),
+            Err(_) => return Ok(())
+        };
+
+        match self.required_fields.iter().find(|field| !fields.contains_key(field.as_str())) {
+            Some(field) => Err(format!("lacks the required field \"{}\"", field)),
+            None => Ok(())
+        }
+    }
+}
+
+pub struct MessageTypeRegistry {
+    message_types: Vec<(String, Option<Box<dyn MessageSchema>>)>
+}
+
+impl MessageTypeRegistry {
+    pub fn new() -> Self {
+        MessageTypeRegistry {
+            message_types: Vec::new()
+        }
+    }
+
+    pub fn register(&mut self, name: &str, schema: Option<Box<dyn MessageSchema>>) {
+        self.message_types.retain(|(registered_name, _)| registered_name != name);
+        self.message_types.push((name.to_string(), schema));
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.message_types.iter().any(|(registered_name, _)| registered_name == name)
+    }
+
+    pub fn schema_of(&self, name: &str) -> Option<&dyn MessageSchema> {
+        self.message_types.iter()
+            .find(|(registered_name, _)| registered_name == name)
+            .and_then(|(_, schema)| schema.as_ref().map(|schema| schema.as_ref()))
+    }
+
+    pub fn find_similar(&self, name: &str) -> Option<&str> {
+        self.message_types.iter()
+            .map(|(registered_name, _)| registered_name.as_str())
+            .find(|registered_name| registered_name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.message_types.iter().map(|(name, _)| name.as_str()).collect()
+    }
+}
+
+impl Default for MessageTypeRegistry {
+    fn default() -> Self {
+        let mut registry = MessageTypeRegistry::new();
+        for (name, layout) in STANDARD_MESSAGE_TYPES.iter() {
+            let schema = JsonFieldSchema::new(&layout.field_names());
+            registry.register(name, Some(Box::from(schema)));
+        }
+        registry
+    }
+}
+
+/// Decodes a message of one of the standard ALICA message types sent in its Cap'n Proto encoding.
+pub fn decode_message(message_type: &str, message: &[u8]) -> Option<Value> {
+    STANDARD_MESSAGE_TYPES.iter()
+        .find(|(name, _)| *name == message_type)
+        .and_then(|(_, layout)| capnp::decode(layout, message))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::alica::{MessageTypeRegistry, MessageSchema, JsonFieldSchema};
+
+    #[test]
+    fn it_finds_known_types_with_different_capitalization() {
+        let registry = MessageTypeRegistry::default();
+
+        assert!(!registry.contains("AlicaEngineinfo"));
+        assert_eq!(registry.find_similar("AlicaEngineinfo"), Some("AlicaEngineInfo"));
+    }
+
+    #[test]
+    fn it_requires_schema_fields_in_json_messages() {
+        let schema = JsonFieldSchema::new(&["senderId", "roleId"]);
+
+        assert!(schema.validate("{\"senderId\": 1, \"roleId\": 2}".as_bytes()).is_ok());
+        assert!(schema.validate("{\"senderId\": 1}".as_bytes()).is_err());
+        assert!(schema.validate(&[0x00, 0x01, 0x02]).is_ok());
+    }
+
+    #[test]
+    fn it_requires_the_fields_of_the_capnp_layout_in_json_messages() {
+        let registry = MessageTypeRegistry::default();
+        let schema = registry.schema_of("RoleSwitch").expect("RoleSwitch has no schema");
+
+        assert!(schema.validate("{\"senderId\": 1, \"roleId\": 2}".as_bytes()).is_ok());
+        assert!(schema.validate("{\"senderID\": 1, \"roleID\": 2}".as_bytes()).is_err());
+    }
+}
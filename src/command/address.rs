@@ -0,0 +1,132 @@
+use crate::command::{self, SawtoothCommand, ExecutionResult};
+use sawtooth_alica_payload::TransactionFamily;
+use sawtooth_alica_payload::payloads::TransactionPayload;
+
+const ADDRESS_LENGTH: usize = 70;
+const NAMESPACE_LENGTH: usize = 6;
+
+const SAWTOOTH_NAMESPACES: [(&str, &str); 4] = [
+    ("000000", "sawtooth_settings"),
+    ("00001d", "sawtooth_identity"),
+    ("00b10c", "block_info"),
+    ("6a4372", "sawtooth_validator_registry")
+];
+
+pub struct NamespaceCommand<'a> {
+    transaction_family: &'a TransactionFamily
+}
+
+impl<'a> NamespaceCommand<'a> {
+    pub fn new(transaction_family: &'a TransactionFamily) -> Self {
+        NamespaceCommand {
+            transaction_family
+        }
+    }
+}
+
+impl<'a> SawtoothCommand for NamespaceCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        println!("{}", self.transaction_family.calculate_namespace());
+        Ok(())
+    }
+}
+
+pub struct ComputeCommand<'a> {
+    transaction_family: &'a TransactionFamily,
+    message: TransactionPayload
+}
+
+impl<'a> ComputeCommand<'a> {
+    pub fn new(transaction_family: &'a TransactionFamily, agent_id: &str, message_type: &str, timestamp: u64) -> Self {
+        ComputeCommand {
+            transaction_family,
+            message: TransactionPayload::new(agent_id, message_type, &[], timestamp)
+        }
+    }
+}
+
+impl<'a> SawtoothCommand for ComputeCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        println!("{}", self.transaction_family.calculate_state_address_for(&self.message));
+        Ok(())
+    }
+}
+
+pub struct ParseCommand<'a> {
+    transaction_family: &'a TransactionFamily,
+    address: String
+}
+
+impl<'a> ParseCommand<'a> {
+    pub fn new(transaction_family: &'a TransactionFamily, address: &str) -> Self {
+        ParseCommand {
+            transaction_family,
+            address: address.to_string()
+        }
+    }
+}
+
+impl<'a> SawtoothCommand for ParseCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        let owner = owner_of(self.transaction_family, &self.address)?;
+        let (namespace, key) = self.address.split_at(NAMESPACE_LENGTH);
+        println!("Address {}:", self.address);
+        println!("-> Namespace: \"{}\"", namespace);
+        println!("-> Key: \"{}\"", key);
+        match owner {
+            Some(family_name) => println!("-> Family: \"{}\"", family_name),
+            None => println!("-> Family: unknown, the namespace does not belong to {} or a Sawtooth core family",
+                             self.transaction_family.name)
+        }
+        Ok(())
+    }
+}
+
+/// Returns the name of the family whose namespace prefixes the address, if it is one the client knows of.
+pub fn owner_of(transaction_family: &TransactionFamily, address: &str) -> Result<Option<String>, command::Error> {
+    if address.len() != ADDRESS_LENGTH || !address.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c)) {
+        return Err(command::Error::ExecutionError(
+            format!("\"{}\" is not a state address, addresses consist of {} lowercase hex characters", address, ADDRESS_LENGTH)));
+    }
+
+    let namespace = &address[..NAMESPACE_LENGTH];
+    if namespace == transaction_family.calculate_namespace() {
+        return Ok(Some(transaction_family.name.clone()));
+    }
+
+    Ok(SAWTOOTH_NAMESPACES.iter()
+        .find(|(sawtooth_namespace, _)| *sawtooth_namespace == namespace)
+        .map(|(_, family_name)| family_name.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use sawtooth_alica_payload::TransactionFamily;
+    use sawtooth_alica_payload::payloads::TransactionPayload;
+    use crate::command::address::owner_of;
+
+    fn create_test_family() -> TransactionFamily {
+        TransactionFamily::new("alica_messages", &vec!["0.1.0".to_string()])
+    }
+
+    #[test]
+    fn it_attributes_computed_addresses_to_the_configured_family() {
+        let family = create_test_family();
+        let payload = TransactionPayload::new("agent1", "AlicaEngineInfo", &[], 1605000000);
+        let address = family.calculate_state_address_for(&payload);
+
+        let owner = owner_of(&family, &address).expect("Address was rejected");
+
+        assert_eq!(owner, Some("alica_messages".to_string()));
+    }
+
+    #[test]
+    fn it_attributes_addresses_to_sawtooth_core_families_and_rejects_malformed_ones() {
+        let family = create_test_family();
+
+        assert_eq!(owner_of(&family, &"0".repeat(70)).ok().unwrap(), Some("sawtooth_settings".to_string()));
+        assert_eq!(owner_of(&family, &("ffffff".to_string() + &"0".repeat(64))).ok().unwrap(), None);
+        assert!(owner_of(&family, "000000").is_err());
+        assert!(owner_of(&family, &"G".repeat(70)).is_err());
+    }
+}
@@ -1,24 +1,330 @@
-use crate::sawtooth::Client;
+use std::collections::{HashMap, HashSet};
+use sawtooth_sdk::messages::transaction::TransactionList;
+use crate::sawtooth::{Client, TransactionOptions};
 use crate::command::{self, SawtoothCommand, ExecutionResult};
+use sawtooth_alica_payload::{payloads, TransactionFamily};
 use sawtooth_alica_payload::payloads::TransactionPayload;
+use crate::validation::PayloadValidator;
+use serde_json::Value;
+
+/// Lets retried submissions skip messages that are already recorded.
+pub struct DuplicateCheck<'a> {
+    transaction_family: &'a TransactionFamily,
+    payload_format: &'a dyn payloads::Format
+}
+
+impl<'a> DuplicateCheck<'a> {
+    pub fn new(transaction_family: &'a TransactionFamily, payload_format: &'a dyn payloads::Format) -> Self {
+        DuplicateCheck {
+            transaction_family,
+            payload_format
+        }
+    }
+
+    /// Returns the address the message is already recorded at. Fails if the address holds another message.
+    fn recorded_address(&self, client: &Client, message: &TransactionPayload) -> Result<Option<String>, command::Error> {
+        let address = self.transaction_family.calculate_state_address_for(message);
+        let data = match client.get_state(&address).map_err(|error| command::Error::from(error))? {
+            Some(data) => data,
+            None => return Ok(None)
+        };
+
+        let recorded_message = self.payload_format.deserialize(&data).map_err(|error| command::Error::from(error))?;
+        if recorded_message.agent_id == message.agent_id
+            && recorded_message.message_type == message.message_type
+            && recorded_message.message_bytes == message.message_bytes
+            && recorded_message.timestamp == message.timestamp {
+            Ok(Some(address))
+        } else {
+            Err(command::Error::ExecutionError(format!("Address {} already holds a different message", address)))
+        }
+    }
+
+    /// Returns the address and the ID of the transaction that already recorded the message.
+    fn recorded_transaction(&self, client: &Client, message: &TransactionPayload)
+                            -> Result<Option<(String, String)>, command::Error> {
+        let address = match self.recorded_address(client, message)? {
+            Some(address) => address,
+            None => return Ok(None)
+        };
+
+        let payload = self.payload_format.serialize(message).map_err(|error| command::Error::from(error))?;
+        let transactions = client.list_transactions().map_err(|error| command::Error::from(error))?;
+        match transactions.iter().rev().find(|transaction| transaction.get_payload() == payload.as_slice()) {
+            Some(transaction) => Ok(Some((address, transaction.get_header_signature().to_string()))),
+            None => Err(command::Error::ExecutionError(
+                format!("No listed transaction recorded the message at {}", address)))
+        }
+    }
+}
 
 pub struct CreateCommand<'a> {
     client: Client<'a>,
-    message: TransactionPayload
+    message: TransactionPayload,
+    options: TransactionOptions,
+    validators: Vec<Box<dyn PayloadValidator + 'a>>,
+    duplicate_check: Option<DuplicateCheck<'a>>
 }
 
 impl<'a> CreateCommand<'a> {
-    pub fn new(client: Client<'a>, message: TransactionPayload) -> Self {
+    pub fn new(client: Client<'a>, message: TransactionPayload, validators: Vec<Box<dyn PayloadValidator + 'a>>) -> Self {
         CreateCommand {
             client,
-            message
+            message,
+            options: TransactionOptions::default(),
+            validators,
+            duplicate_check: None
         }
     }
+
+    pub fn with_options(mut self, options: TransactionOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_duplicate_check(mut self, duplicate_check: DuplicateCheck<'a>) -> Self {
+        self.duplicate_check = Some(duplicate_check);
+        self
+    }
 }
 
 impl<'a> SawtoothCommand for CreateCommand<'a> {
     fn execute(&self) -> ExecutionResult {
-        let messages = vec![&self.message];
-        self.client.create_batch(&messages).map_err(|error| command::Error::from(error))
+        for validator in &self.validators {
+            validator.validate(&self.message).map_err(|error| command::Error::from(error))?;
+        }
+
+        if let Some(duplicate_check) = &self.duplicate_check {
+            if let Some(address) = duplicate_check.recorded_address(&self.client, &self.message)? {
+                println!("Skipped message, it is already recorded at {}", address);
+                return Ok(());
+            }
+        }
+
+        let messages = vec![(&self.message, &self.options)];
+        let transaction_ids = self.client.create_batch_with_options(&messages).map_err(|error| command::Error::from(error))?;
+        for transaction_id in transaction_ids {
+            println!("Transaction {}", transaction_id);
+        }
+        Ok(())
+    }
+}
+
+/// Submits transactions that were signed elsewhere as a single batch signed with the batcher key.
+pub struct WrapCommand<'a> {
+    client: Client<'a>,
+    inputs: Vec<Vec<u8>>
+}
+
+impl<'a> WrapCommand<'a> {
+    pub fn new(client: Client<'a>, inputs: Vec<Vec<u8>>) -> Self {
+        WrapCommand {
+            client,
+            inputs
+        }
+    }
+}
+
+impl<'a> SawtoothCommand for WrapCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        let mut transactions = Vec::new();
+        for input in &self.inputs {
+            let mut transaction_list = protobuf::parse_from_bytes::<TransactionList>(input)
+                .map_err(|_| command::Error::ExecutionError("Input does not contain a transaction list".to_string()))?;
+            transactions.extend(transaction_list.take_transactions().into_iter());
+        }
+        if transactions.is_empty() {
+            return Err(command::Error::ExecutionError("No transactions to wrap".to_string()));
+        }
+
+        let transaction_ids = self.client.submit_transactions(transactions).map_err(|error| command::Error::from(error))?;
+        for transaction_id in transaction_ids {
+            println!("Transaction {}", transaction_id);
+        }
+        Ok(())
+    }
+}
+
+/// A single line of an import file.
+pub struct ImportEntry {
+    pub label: Option<String>,
+    pub message: TransactionPayload,
+    pub options: TransactionOptions,
+    pub depends_on: Vec<String>
+}
+
+impl ImportEntry {
+    /// Dependencies name either the label of an earlier entry or the ID of a committed transaction.
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let json: Value = serde_json::from_str(line).map_err(|error| error.to_string())?;
+        let message = TransactionPayload::new(
+            &string_field(&json, "agent_id")?,
+            &string_field(&json, "message_type")?,
+            string_field(&json, "message")?.as_bytes(),
+            json.get("timestamp").and_then(|timestamp| timestamp.as_u64())
+                .ok_or_else(|| "Field \"timestamp\" is missing or not an unsigned integer".to_string())?
+        );
+        let label = match json.get("label") {
+            Some(_) => Some(string_field(&json, "label")?),
+            None => None
+        };
+
+        Ok(ImportEntry {
+            label,
+            message,
+            options: TransactionOptions {
+                inputs: string_list_field(&json, "inputs")?,
+                outputs: string_list_field(&json, "outputs")?,
+                dependencies: Vec::new(),
+                idempotency_key: match json.get("idempotency_key") {
+                    Some(_) => Some(string_field(&json, "idempotency_key")?),
+                    None => None
+                }
+            },
+            depends_on: string_list_field(&json, "depends_on")?
+        })
+    }
+}
+
+/// Submits every entry as its own batch so dependencies can resolve to earlier transaction IDs.
+pub struct ImportCommand<'a> {
+    client: Client<'a>,
+    input: String,
+    validators: Vec<Box<dyn PayloadValidator + 'a>>,
+    duplicate_check: Option<DuplicateCheck<'a>>
+}
+
+impl<'a> ImportCommand<'a> {
+    pub fn new(client: Client<'a>, input: String, validators: Vec<Box<dyn PayloadValidator + 'a>>) -> Self {
+        ImportCommand {
+            client,
+            input,
+            validators,
+            duplicate_check: None
+        }
+    }
+
+    pub fn with_duplicate_check(mut self, duplicate_check: DuplicateCheck<'a>) -> Self {
+        self.duplicate_check = Some(duplicate_check);
+        self
+    }
+
+    fn parse_entries(&self) -> Result<Vec<(usize, ImportEntry)>, command::Error> {
+        let mut entries: Vec<(usize, ImportEntry)> = Vec::new();
+        for (index, line) in self.input.lines().enumerate() {
+            let line_number = index + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let entry = ImportEntry::parse(line)
+                .map_err(|error| command::Error::ExecutionError(format!("Line {}: {}", line_number, error)))?;
+            for validator in &self.validators {
+                validator.validate(&entry.message).map_err(|error| match command::Error::from(error) {
+                    command::Error::ExecutionError(error) =>
+                        command::Error::ExecutionError(format!("Line {}: {}", line_number, error))
+                })?;
+            }
+            entries.push((line_number, entry));
+        }
+
+        let mut earlier_labels: HashSet<&str> = HashSet::new();
+        for (line_number, entry) in &entries {
+            for dependency in &entry.depends_on {
+                resolve_dependency(dependency, |label| earlier_labels.get(label).map(|label| label.to_string()))
+                    .map_err(|error| command::Error::ExecutionError(format!("Line {}: {}", line_number, error)))?;
+            }
+            if let Some(label) = &entry.label {
+                if !earlier_labels.insert(label.as_str()) {
+                    return Err(command::Error::ExecutionError(
+                        format!("Line {}: Label \"{}\" is used more than once", line_number, label)));
+                }
+            }
+        }
+        Ok(entries)
+    }
+}
+
+impl<'a> SawtoothCommand for ImportCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        let entries = self.parse_entries()?;
+
+        let mut transaction_ids: HashMap<String, String> = HashMap::new();
+        let mut submitted = 0;
+        let mut skipped = 0;
+        for (line_number, entry) in &entries {
+            let mut options = entry.options.clone();
+            for dependency in &entry.depends_on {
+                options.dependencies.push(resolve_dependency(dependency, |label| transaction_ids.get(label).cloned())
+                    .map_err(|error| command::Error::ExecutionError(format!("Line {}: {}", line_number, error)))?);
+            }
+
+            let stopped = |error: command::Error| match error {
+                command::Error::ExecutionError(error) => command::Error::ExecutionError(format!(
+                    "Import stopped at line {} after {} submitted entries: {}", line_number, submitted, error))
+            };
+            let recorded_transaction = match &self.duplicate_check {
+                Some(duplicate_check) => duplicate_check.recorded_transaction(&self.client, &entry.message)
+                    .map_err(stopped)?,
+                None => None
+            };
+
+            let created_ids = match recorded_transaction {
+                Some((address, transaction_id)) => {
+                    println!("Line {}: Already recorded at {} by transaction {}, skipped", line_number, address, transaction_id);
+                    skipped += 1;
+                    vec![transaction_id]
+                },
+                None => {
+                    let created_ids = self.client.create_batch_with_options(&[(&entry.message, &options)])
+                        .map_err(|error| stopped(command::Error::from(error)))?;
+                    submitted += 1;
+                    for transaction_id in &created_ids {
+                        match &entry.label {
+                            Some(label) => println!("Line {} ({}): Transaction {}", line_number, label, transaction_id),
+                            None => println!("Line {}: Transaction {}", line_number, transaction_id)
+                        }
+                    }
+                    created_ids
+                }
+            };
+
+            if let (Some(label), Some(transaction_id)) = (&entry.label, created_ids.into_iter().next()) {
+                transaction_ids.insert(label.clone(), transaction_id);
+            }
+        }
+
+        if self.duplicate_check.is_some() {
+            println!("Submitted {} entries, skipped {} already recorded entries", submitted, skipped);
+        }
+        Ok(())
     }
-}
\ No newline at end of file
+}
+
+fn resolve_dependency<F: Fn(&str) -> Option<String>>(dependency: &str, transaction_id_of: F) -> Result<String, String> {
+    if let Some(transaction_id) = transaction_id_of(dependency) {
+        return Ok(transaction_id);
+    }
+    if dependency.len() == 128 && dependency.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(dependency.to_string());
+    }
+    Err(format!("Dependency \"{}\" is neither the label of an earlier entry nor a transaction ID", dependency))
+}
+
+fn string_field(json: &Value, name: &str) -> Result<String, String> {
+    json.get(name)
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+        .ok_or_else(|| format!("Field \"{}\" is missing or not a string", name))
+}
+
+fn string_list_field(json: &Value, name: &str) -> Result<Vec<String>, String> {
+    match json.get(name) {
+        Some(Value::Array(values)) => values.iter()
+            .map(|value| value.as_str().map(|value| value.to_string())
+                .ok_or_else(|| format!("Field \"{}\" may only contain strings", name)))
+            .collect(),
+        None => Ok(Vec::new()),
+        _ => Err(format!("Field \"{}\" is not a list", name))
+    }
+}
@@ -0,0 +1,127 @@
+use std::io::{self, Stdout};
+use std::time::{Duration, Instant};
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use sawtooth_alica_payload::payloads::{self, TransactionPayload};
+use tui::backend::CrosstermBackend;
+use tui::Terminal;
+use crate::sawtooth::Client;
+use crate::command::{self, SawtoothCommand, ExecutionResult};
+use crate::dashboard::{view, MissionState};
+
+/// Switches the terminal to the dashboard and back when dropped, also if drawing fails.
+struct DashboardTerminal {
+    terminal: Terminal<CrosstermBackend<Stdout>>
+}
+
+impl DashboardTerminal {
+    fn open() -> Result<Self, command::Error> {
+        terminal::enable_raw_mode().map_err(|error| terminal_error(&error))?;
+        let mut stdout = io::stdout();
+        crossterm::execute!(stdout, EnterAlternateScreen).map_err(|error| terminal_error(&error))?;
+        let terminal = Terminal::new(CrosstermBackend::new(stdout)).map_err(|error| terminal_error(&error))?;
+        Ok(DashboardTerminal { terminal })
+    }
+}
+
+impl Drop for DashboardTerminal {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+        let _ = crossterm::execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
+        let _ = self.terminal.show_cursor();
+    }
+}
+
+pub struct DashboardCommand<'a> {
+    client: Client<'a>,
+    namespace: String,
+    payload_format: &'a dyn payloads::Format,
+    poll_interval: Duration,
+    silence_threshold: Duration
+}
+
+impl<'a> DashboardCommand<'a> {
+    pub fn new(client: Client<'a>, namespace: &str, payload_format: &'a dyn payloads::Format) -> Self {
+        DashboardCommand {
+            client,
+            namespace: namespace.to_string(),
+            payload_format,
+            poll_interval: Duration::from_secs(1),
+            silence_threshold: Duration::from_secs(10)
+        }
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Agents without a new message for longer than this are highlighted.
+    pub fn with_silence_threshold(mut self, silence_threshold: Duration) -> Self {
+        self.silence_threshold = silence_threshold;
+        self
+    }
+
+    /// Returns the decodable entries of the namespace and the number of entries that could not be decoded.
+    fn snapshot(&self) -> Result<(Vec<(String, TransactionPayload)>, usize), command::Error> {
+        let state_entries = self.client.list_state_entries().map_err(|error| command::Error::from(error))?;
+
+        let mut payloads = Vec::new();
+        let mut undecodable_entries = 0;
+        for entry in state_entries.iter().filter(|entry| entry.get_address().starts_with(&self.namespace)) {
+            match self.payload_format.deserialize(entry.get_data()) {
+                Ok(payload) => payloads.push((entry.get_address().to_string(), payload)),
+                Err(_) => undecodable_entries += 1
+            }
+        }
+        Ok((payloads, undecodable_entries))
+    }
+}
+
+impl<'a> SawtoothCommand for DashboardCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        let mut state = MissionState::new(Instant::now());
+        let mut settings = view::ViewSettings {
+            silence_threshold: self.silence_threshold,
+            status_line: "Waiting for the first snapshot".to_string()
+        };
+        let mut dashboard = DashboardTerminal::open()?;
+        let mut next_poll = Instant::now();
+
+        loop {
+            let now = Instant::now();
+            if now >= next_poll {
+                // A failed poll keeps the last known state on screen, the network may only be slow.
+                settings.status_line = match self.snapshot() {
+                    Ok((entries, 0)) => {
+                        state.update(entries, now);
+                        format!("Updated {}s after start", now.duration_since(state.started_at()).as_secs())
+                    },
+                    Ok((entries, undecodable_entries)) => {
+                        state.update(entries, now);
+                        format!("Skipped {} undecodable entries", undecodable_entries)
+                    },
+                    Err(command::Error::ExecutionError(message)) => format!("Polling failed: {}", message)
+                };
+                next_poll = now + self.poll_interval;
+            }
+
+            dashboard.terminal.draw(|frame| view::draw(frame, &state, &settings, now))
+                .map_err(|error| terminal_error(&error))?;
+
+            let timeout = next_poll.saturating_duration_since(Instant::now()).min(Duration::from_millis(250));
+            if event::poll(timeout).map_err(|error| terminal_error(&error))? {
+                if let Event::Key(key) = event::read().map_err(|error| terminal_error(&error))? {
+                    let is_interrupt = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                    if key.code == KeyCode::Char('q') || key.code == KeyCode::Esc || is_interrupt {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn terminal_error(error: &dyn std::fmt::Display) -> command::Error {
+    command::Error::ExecutionError(format!("Terminal could not be used: {}", error))
+}
@@ -0,0 +1,153 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use sawtooth_sdk::messages::block::BlockHeader;
+use sawtooth_alica_payload::payloads;
+use serde_json::{json, Map, Value};
+use crate::sawtooth::{helper, Client};
+use crate::command::{self, SawtoothCommand, ExecutionResult};
+use crate::filter::{self, TransactionPayloadFilter};
+use crate::output;
+
+const JSON_LINES_FILE_NAME: &str = "messages.jsonl";
+const CSV_FILE_NAME: &str = "messages.csv";
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+const CSV_COLUMNS: [&str; 6] = ["address", "agent_id", "message_type", "timestamp", "message_encoding", "message"];
+
+/// Writes the messages at the chain head with a manifest naming the block and state root.
+pub struct ExportCommand<'a> {
+    client: Client<'a>,
+    namespace: String,
+    payload_format: &'a dyn payloads::Format,
+    filters: Vec<Box<dyn TransactionPayloadFilter>>,
+    filter_expressions: Vec<String>,
+    directory: PathBuf
+}
+
+impl<'a> ExportCommand<'a> {
+    pub fn new(client: Client<'a>, namespace: &str, payload_format: &'a dyn payloads::Format,
+               filters: Vec<Box<dyn TransactionPayloadFilter>>, directory: &Path) -> Self {
+        ExportCommand {
+            client,
+            namespace: namespace.to_string(),
+            payload_format,
+            filters,
+            filter_expressions: Vec::new(),
+            directory: directory.to_path_buf()
+        }
+    }
+
+    /// Filter expressions as they were given, recorded in the manifest to reproduce the export.
+    pub fn with_filter_expressions(mut self, filter_expressions: Vec<String>) -> Self {
+        self.filter_expressions = filter_expressions;
+        self
+    }
+
+    fn write(&self, file_name: &str, contents: &[u8]) -> Result<Value, command::Error> {
+        let path = self.directory.join(file_name);
+        fs::write(&path, contents)
+            .map_err(|error| command::Error::ExecutionError(format!("Could not write {}: {}", path.display(), error)))?;
+        Ok(json!({
+            "name": file_name,
+            "bytes": contents.len(),
+            "sha512": helper::calculate_checksum(&contents)
+        }))
+    }
+}
+
+impl<'a> SawtoothCommand for ExportCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        let block = match self.client.get_chain_head().map_err(|error| command::Error::from(error))? {
+            Some(block) => block,
+            None => return Err(command::Error::ExecutionError("The chain has no blocks to export from".to_string()))
+        };
+        let header = protobuf::parse_from_bytes::<BlockHeader>(block.get_header())
+            .map_err(|_| command::Error::ExecutionError(format!("Invalid header in block {}", block.get_header_signature())))?;
+
+        // Reading at the state root of the head keeps the files consistent with the manifest even if
+        // blocks are committed during the export.
+        let mut state_entries = self.client.list_state_entries_at(header.get_state_root_hash())
+            .map_err(|error| command::Error::from(error))?;
+        state_entries.retain(|entry| entry.get_address().starts_with(&self.namespace));
+        state_entries.sort_by(|entry, other_entry| entry.get_address().cmp(other_entry.get_address()));
+
+        let mut json_lines = String::new();
+        let mut csv = CSV_COLUMNS.join(",") + "\n";
+        let mut exported_messages = 0;
+        for entry in &state_entries {
+            let payload = self.payload_format.deserialize(entry.get_data()).map_err(|error| command::Error::from(error))?;
+            let payload = match filter::apply_all(&self.filters, payload) {
+                Some(payload) => payload,
+                None => continue
+            };
+
+            let mut json_payload = output::payload_as_json(&payload);
+            json_payload.insert("address".to_string(), Value::from(entry.get_address()));
+            csv.push_str(&csv_row(&json_payload));
+            csv.push('\n');
+            // The raw message lets the replay command publish capnp messages exactly as they were sent.
+            json_payload.insert("message_hex".to_string(), Value::from(data_encoding::HEXLOWER.encode(&payload.message_bytes)));
+            json_lines.push_str(&Value::Object(json_payload).to_string());
+            json_lines.push('\n');
+            exported_messages += 1;
+        }
+
+        fs::create_dir_all(&self.directory)
+            .map_err(|error| command::Error::ExecutionError(format!("Could not create {}: {}", self.directory.display(), error)))?;
+        let files = vec![
+            self.write(JSON_LINES_FILE_NAME, json_lines.as_bytes())?,
+            self.write(CSV_FILE_NAME, csv.as_bytes())?
+        ];
+        let manifest = json!({
+            "chain_head": {
+                "block_id": block.get_header_signature(),
+                "block_num": header.get_block_num()
+            },
+            "state_root": header.get_state_root_hash(),
+            "namespace": self.namespace,
+            "filters": self.filter_expressions,
+            "counts": {
+                "entries": state_entries.len(),
+                "exported_messages": exported_messages
+            },
+            "files": files
+        });
+        let manifest = serde_json::to_string_pretty(&manifest).expect("Manifest can not be rendered") + "\n";
+        self.write(MANIFEST_FILE_NAME, manifest.as_bytes())?;
+
+        println!("Exported {} of {} messages at block {} to {}", exported_messages, state_entries.len(),
+                 header.get_block_num(), self.directory.display());
+        Ok(())
+    }
+}
+
+fn csv_row(json_payload: &Map<String, Value>) -> String {
+    CSV_COLUMNS.iter()
+        .map(|column| match json_payload.get(*column) {
+            Some(Value::String(text)) => csv_field(text),
+            Some(value) => csv_field(&value.to_string()),
+            None => String::new()
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Quotes fields that contain separators, quotes or line breaks as RFC 4180 describes.
+fn csv_field(value: &str) -> String {
+    if value.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::command::export::csv_field;
+
+    #[test]
+    fn it_quotes_csv_fields_with_separators_and_quotes() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
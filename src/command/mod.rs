@@ -1,7 +1,18 @@
+pub mod address;
 pub mod batch;
+pub mod dashboard;
+pub mod export;
+pub mod network;
+pub mod receipt;
+pub mod record;
+pub mod replay;
+pub mod sync;
 pub mod state;
+pub mod transaction;
+pub mod verify;
 
 use crate::sawtooth;
+use crate::validation;
 use sawtooth_alica_payload::payloads;
 
 #[derive(Debug)]
@@ -24,6 +35,17 @@ impl From<sawtooth::Error> for Error {
             sawtooth::Error::InternalError => format!("Internal error when submitting batch"),
             sawtooth::Error::FullQueue => format!("Batch request queue of targeted validator is full!"),
             sawtooth::Error::BatchStatusUnset => format!("No status set for batch"),
+            sawtooth::Error::UnsupportedUrl(url) => format!("No transport available for validator URL {}", url),
+            sawtooth::Error::UnsupportedRequest(request_type) =>
+                format!("Request {} is not supported by the selected transport", request_type),
+            sawtooth::Error::StorageError(location) => format!("Failed to access ledger storage at {}", location),
+            sawtooth::Error::VerificationError(violations) => format!("Transactions failed verification: {}", violations),
+            sawtooth::Error::StateUnavailable(status) => format!("State could not be read, the validator answered {}", status),
+            sawtooth::Error::ReceiptsUnavailable(status) =>
+                format!("Receipts could not be read, the validator answered {}", status),
+            sawtooth::Error::NetworkStatusUnavailable(status) =>
+                format!("Network status could not be read, the validator answered {}", status),
+            sawtooth::Error::BlocksUnavailable(status) => format!("Blocks could not be listed, the validator answered {}", status),
         };
 
         Error::ExecutionError(message)
@@ -41,6 +63,16 @@ impl From<payloads::Error> for Error {
     }
 }
 
+impl From<validation::Error> for Error {
+    fn from(error: validation::Error) -> Self {
+        let message = match error {
+            validation::Error::InvalidField(field, reason) => format!("Field \"{}\" {}", field, reason)
+        };
+
+        Error::ExecutionError(message)
+    }
+}
+
 pub type ExecutionResult = Result<(), Error>;
 
 pub trait SawtoothCommand {
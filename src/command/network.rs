@@ -0,0 +1,153 @@
+use sawtooth_sdk::messages::block::BlockHeader;
+use sawtooth_sdk::messages::setting::Setting;
+use serde_json::{json, Value};
+use crate::sawtooth::{helper, Client};
+use crate::command::{self, SawtoothCommand, ExecutionResult};
+use crate::output::{self, OutputFormat};
+
+const TRANSACTION_FAMILIES_SETTING: &str = "sawtooth.validator.transaction_families";
+
+/// What the validator settings say about the transaction processors the validator accepts.
+enum ProcessorRegistration {
+    /// The setting lists the family with these versions, which is empty if the family is missing.
+    Listed(Vec<String>),
+    /// The setting is not present, so the validator does not restrict the transaction families.
+    Unrestricted
+}
+
+struct ChainHead {
+    block_id: String,
+    block_num: u64
+}
+
+pub struct StatusCommand<'a> {
+    client: Client<'a>,
+    family_name: String,
+    family_version: String,
+    output_format: OutputFormat
+}
+
+impl<'a> StatusCommand<'a> {
+    pub fn new(client: Client<'a>, family_name: &str, family_version: &str) -> Self {
+        StatusCommand {
+            client,
+            family_name: family_name.to_string(),
+            family_version: family_version.to_string(),
+            output_format: OutputFormat::Text
+        }
+    }
+
+    pub fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    fn chain_head(&self) -> Result<Option<ChainHead>, command::Error> {
+        let block = match self.client.get_chain_head().map_err(|error| command::Error::from(error))? {
+            Some(block) => block,
+            None => return Ok(None)
+        };
+        let header = protobuf::parse_from_bytes::<BlockHeader>(block.get_header())
+            .map_err(|_| command::Error::ExecutionError(format!("Invalid header in block {}", block.get_header_signature())))?;
+
+        Ok(Some(ChainHead {
+            block_id: block.get_header_signature().to_string(),
+            block_num: header.get_block_num()
+        }))
+    }
+
+    fn processor_registration(&self) -> Result<ProcessorRegistration, command::Error> {
+        let address = helper::setting_address(TRANSACTION_FAMILIES_SETTING);
+        let data = match self.client.get_state(&address).map_err(|error| command::Error::from(error))? {
+            Some(data) => data,
+            None => return Ok(ProcessorRegistration::Unrestricted)
+        };
+        let setting = protobuf::parse_from_bytes::<Setting>(&data)
+            .map_err(|_| command::Error::ExecutionError(format!("Setting {} is malformed", TRANSACTION_FAMILIES_SETTING)))?;
+        let value = match setting.get_entries().iter().find(|entry| entry.get_key() == TRANSACTION_FAMILIES_SETTING) {
+            Some(entry) => entry.get_value(),
+            None => return Ok(ProcessorRegistration::Unrestricted)
+        };
+
+        let families: Value = serde_json::from_str(value)
+            .map_err(|_| command::Error::ExecutionError(format!("Setting {} is not valid JSON", TRANSACTION_FAMILIES_SETTING)))?;
+        let versions = families.as_array().map(|families| families.as_slice()).unwrap_or(&[]).iter()
+            .filter(|family| family.get("family").and_then(|name| name.as_str()) == Some(self.family_name.as_str()))
+            .filter_map(|family| family.get("version").and_then(|version| version.as_str()))
+            .map(|version| version.to_string())
+            .collect();
+        Ok(ProcessorRegistration::Listed(versions))
+    }
+
+    fn problems_of(&self, chain_head: &Option<ChainHead>, registration: &ProcessorRegistration) -> Vec<String> {
+        let mut problems = Vec::new();
+        if chain_head.is_none() {
+            problems.push("the validator has no chain head".to_string());
+        }
+        if let ProcessorRegistration::Listed(versions) = registration {
+            if !versions.contains(&self.family_version) {
+                problems.push(format!("{} {} is not an accepted transaction family", self.family_name, self.family_version));
+            }
+        }
+        problems
+    }
+}
+
+impl<'a> SawtoothCommand for StatusCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        let status = self.client.get_status().map_err(|error| command::Error::from(error))?;
+        let peers = self.client.get_peers().map_err(|error| command::Error::from(error))?;
+        let chain_head = self.chain_head()?;
+        let registration = self.processor_registration()?;
+        let problems = self.problems_of(&chain_head, &registration);
+
+        match self.output_format {
+            OutputFormat::Text => {
+                println!("Validator endpoint: {}", status.get_endpoint());
+                println!("Peers: {}", peers.len());
+                for peer in &peers {
+                    println!("-> {}", peer);
+                }
+                match &chain_head {
+                    Some(chain_head) => println!("Chain head: block {} ({})", chain_head.block_num, chain_head.block_id),
+                    None => println!("Chain head: none")
+                }
+                match &registration {
+                    ProcessorRegistration::Listed(versions) if versions.is_empty() =>
+                        println!("Processor: {} is not listed in {}", self.family_name, TRANSACTION_FAMILIES_SETTING),
+                    ProcessorRegistration::Listed(versions) =>
+                        println!("Processor: {} is registered with versions {}", self.family_name, versions.join(", ")),
+                    ProcessorRegistration::Unrestricted =>
+                        println!("Processor: {} is not set, the validator accepts every transaction family",
+                                 TRANSACTION_FAMILIES_SETTING)
+                }
+            },
+            OutputFormat::Json => {
+                let (processor_registered, registered_versions) = match &registration {
+                    ProcessorRegistration::Listed(versions) =>
+                        (Value::from(versions.contains(&self.family_version)), Value::from(versions.clone())),
+                    ProcessorRegistration::Unrestricted => (Value::Null, Value::Null)
+                };
+                output::print_json_value(&json!({
+                    "endpoint": status.get_endpoint(),
+                    "peers": peers,
+                    "chain_head": chain_head.as_ref().map(|chain_head| json!({
+                        "block_id": chain_head.block_id,
+                        "block_num": chain_head.block_num
+                    })),
+                    "family_name": self.family_name,
+                    "family_version": self.family_version,
+                    "processor_registered": processor_registered,
+                    "registered_versions": registered_versions,
+                    "problems": problems
+                }))
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(command::Error::ExecutionError(format!("The network is not healthy: {}", problems.join(", "))))
+        }
+    }
+}
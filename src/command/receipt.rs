@@ -0,0 +1,123 @@
+use sawtooth_sdk::messages::transaction_receipt::{TransactionReceipt, StateChange, StateChange_Type};
+use sawtooth_sdk::messages::events::Event;
+use sawtooth_alica_payload::payloads;
+use protobuf::ProtobufEnum;
+use serde_json::{json, Map, Value};
+use crate::sawtooth::Client;
+use crate::command::{self, SawtoothCommand, ExecutionResult};
+use crate::output::{self, OutputFormat};
+
+pub struct ShowCommand<'a> {
+    client: Client<'a>,
+    transaction_ids: Vec<String>,
+    payload_format: &'a dyn payloads::Format,
+    output_format: OutputFormat
+}
+
+impl<'a> ShowCommand<'a> {
+    pub fn new(client: Client<'a>, transaction_ids: Vec<String>, payload_format: &'a dyn payloads::Format) -> Self {
+        ShowCommand {
+            client,
+            transaction_ids,
+            payload_format,
+            output_format: OutputFormat::Text
+        }
+    }
+
+    pub fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    fn print_receipt(&self, receipt: &TransactionReceipt) {
+        println!("Receipt of transaction {}:", receipt.get_transaction_id());
+        for state_change in receipt.get_state_changes() {
+            println!("-> {} {}", change_type_of(state_change), state_change.get_address());
+            if state_change.get_field_type() == StateChange_Type::DELETE {
+                continue;
+            }
+            match self.payload_format.deserialize(state_change.get_value()) {
+                Ok(payload) => output::print_payload(&payload),
+                Err(_) => println!("-> Value (hex): {}", data_encoding::HEXLOWER.encode(state_change.get_value()))
+            }
+        }
+        for event in receipt.get_events() {
+            let attributes: Vec<String> = event.get_attributes().iter()
+                .map(|attribute| format!("{}={}", attribute.get_key(), attribute.get_value()))
+                .collect();
+            println!("-> Event \"{}\" ({})", event.get_event_type(), attributes.join(", "));
+            if !event.get_data().is_empty() {
+                println!("-> Event Data (hex): {}", data_encoding::HEXLOWER.encode(event.get_data()));
+            }
+        }
+        for data in receipt.get_data() {
+            println!("-> Data (hex): {}", data_encoding::HEXLOWER.encode(data));
+        }
+    }
+
+    fn receipt_as_json(&self, receipt: &TransactionReceipt) -> Value {
+        let state_changes: Vec<Value> = receipt.get_state_changes().iter()
+            .map(|state_change| self.state_change_as_json(state_change))
+            .collect();
+        let events: Vec<Value> = receipt.get_events().iter().map(event_as_json).collect();
+        let data: Vec<Value> = receipt.get_data().iter()
+            .map(|data| Value::from(data_encoding::HEXLOWER.encode(data)))
+            .collect();
+
+        json!({
+            "transaction_id": receipt.get_transaction_id(),
+            "state_changes": state_changes,
+            "events": events,
+            "data": data
+        })
+    }
+
+    fn state_change_as_json(&self, state_change: &StateChange) -> Value {
+        let mut json_state_change = Map::new();
+        json_state_change.insert("address".to_string(), Value::from(state_change.get_address()));
+        json_state_change.insert("type".to_string(), Value::from(state_change.get_field_type().descriptor().name()));
+        if state_change.get_field_type() != StateChange_Type::DELETE {
+            match self.payload_format.deserialize(state_change.get_value()) {
+                Ok(payload) => json_state_change.insert("payload".to_string(), Value::Object(output::payload_as_json(&payload))),
+                Err(_) => json_state_change.insert("value".to_string(),
+                                                   Value::from(data_encoding::HEXLOWER.encode(state_change.get_value())))
+            };
+        }
+        Value::Object(json_state_change)
+    }
+}
+
+impl<'a> SawtoothCommand for ShowCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        let receipts = self.client.get_receipts(&self.transaction_ids).map_err(|error| command::Error::from(error))?;
+
+        match self.output_format {
+            OutputFormat::Text => for receipt in &receipts {
+                self.print_receipt(receipt);
+            },
+            OutputFormat::Json => output::print_json(receipts.iter().map(|receipt| self.receipt_as_json(receipt)).collect())
+        }
+
+        Ok(())
+    }
+}
+
+fn change_type_of(state_change: &StateChange) -> &'static str {
+    match state_change.get_field_type() {
+        StateChange_Type::SET => "Set",
+        StateChange_Type::DELETE => "Delete",
+        StateChange_Type::TYPE_UNSET => "Unknown change of"
+    }
+}
+
+fn event_as_json(event: &Event) -> Value {
+    let attributes: Map<String, Value> = event.get_attributes().iter()
+        .map(|attribute| (attribute.get_key().to_string(), Value::from(attribute.get_value())))
+        .collect();
+
+    json!({
+        "event_type": event.get_event_type(),
+        "attributes": attributes,
+        "data": data_encoding::HEXLOWER.encode(event.get_data())
+    })
+}
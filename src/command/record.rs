@@ -0,0 +1,362 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use sawtooth_alica_payload::payloads::TransactionPayload;
+use serde_json::json;
+use crate::alica::{self, capnp};
+use crate::alica::capnzero::Subscriber;
+use crate::sawtooth::{self, Client};
+use crate::command::{self, replay, SawtoothCommand, ExecutionResult};
+use crate::filter::{self, TransactionPayloadFilter};
+use crate::validation::PayloadValidator;
+
+const MESSAGES_FILE_NAME: &str = "messages";
+const SUBMITTED_FILE_NAME: &str = "submitted";
+const COMPACTED_FILE_NAME: &str = "messages.compacted";
+const COMPACTION_THRESHOLD: u64 = 64 * 1024;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Messages not submitted yet, kept on disk so that neither a restart nor an unreachable validator loses them.
+struct Spool {
+    directory: PathBuf,
+    file: File,
+    /// Bytes of the messages file that are submitted, a crash before it is updated resubmits them.
+    submitted_bytes: u64,
+    pending: VecDeque<(TransactionPayload, u64)>
+}
+
+impl Spool {
+    fn open(directory: &Path) -> Result<Self, command::Error> {
+        fs::create_dir_all(directory).map_err(|error| spool_error(directory, &error))?;
+        let messages_path = directory.join(MESSAGES_FILE_NAME);
+        let contents = fs::read(&messages_path).unwrap_or_default();
+        let submitted_bytes = match fs::read_to_string(directory.join(SUBMITTED_FILE_NAME)) {
+            Ok(submitted_bytes) => submitted_bytes.trim().parse::<u64>()
+                .map_err(|_| command::Error::ExecutionError(format!("Spool in {} is corrupt", directory.display())))?,
+            Err(_) => 0
+        };
+
+        let mut pending = VecDeque::new();
+        let mut complete_bytes = submitted_bytes;
+        for line in contents.get(submitted_bytes as usize..).unwrap_or_default().split_inclusive(|byte| *byte == b'\n') {
+            // A line without a line break was cut off while the recorder stopped and is dropped.
+            if !line.ends_with(b"\n") {
+                break;
+            }
+            let payload = String::from_utf8(line.to_vec()).map_err(|error| error.to_string())
+                .and_then(|line| replay::payload_of_export_line(&line))
+                .map_err(|reason| command::Error::ExecutionError(format!("Spool in {} is corrupt: {}", directory.display(), reason)))?;
+            pending.push_back((payload, line.len() as u64));
+            complete_bytes += line.len() as u64;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&messages_path)
+            .map_err(|error| spool_error(directory, &error))?;
+        file.set_len(complete_bytes).map_err(|error| spool_error(directory, &error))?;
+        Ok(Spool {
+            directory: directory.to_path_buf(),
+            file,
+            submitted_bytes,
+            pending
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn push(&mut self, payload: TransactionPayload) -> Result<(), command::Error> {
+        let line = spool_line_of(&payload);
+        self.file.write_all(line.as_bytes()).map_err(|error| spool_error(&self.directory, &error))?;
+        self.pending.push_back((payload, line.len() as u64));
+        Ok(())
+    }
+
+    fn oldest(&self, count: usize) -> Vec<&TransactionPayload> {
+        self.pending.iter().take(count).map(|(payload, _)| payload).collect()
+    }
+
+    /// Removes the oldest messages after they were submitted.
+    fn acknowledge(&mut self, count: usize) -> Result<(), command::Error> {
+        for (_, line_length) in self.pending.drain(..count.min(self.pending.len())) {
+            self.submitted_bytes += line_length;
+        }
+        if self.pending.is_empty() {
+            self.file.set_len(0).map_err(|error| spool_error(&self.directory, &error))?;
+            self.submitted_bytes = 0;
+        } else if self.submitted_bytes > COMPACTION_THRESHOLD {
+            self.compact()?;
+        }
+        fs::write(self.directory.join(SUBMITTED_FILE_NAME), self.submitted_bytes.to_string())
+            .map_err(|error| spool_error(&self.directory, &error))
+    }
+
+    /// Replaces the messages file by one holding only the pending messages.
+    fn compact(&mut self) -> Result<(), command::Error> {
+        let compacted_path = self.directory.join(COMPACTED_FILE_NAME);
+        let messages_path = self.directory.join(MESSAGES_FILE_NAME);
+        let lines: String = self.pending.iter().map(|(payload, _)| spool_line_of(payload)).collect();
+        fs::write(&compacted_path, lines).map_err(|error| spool_error(&self.directory, &error))?;
+        // Resetting the submitted bytes first makes a crash before the rename resubmit messages instead of losing them.
+        fs::write(self.directory.join(SUBMITTED_FILE_NAME), "0").map_err(|error| spool_error(&self.directory, &error))?;
+        fs::rename(&compacted_path, &messages_path).map_err(|error| spool_error(&self.directory, &error))?;
+        self.file = OpenOptions::new().append(true).open(&messages_path)
+            .map_err(|error| spool_error(&self.directory, &error))?;
+        self.submitted_bytes = 0;
+        Ok(())
+    }
+}
+
+/// Records the messages ALICA agents publish over capnzero in the chain.
+pub struct RecordCommand<'a> {
+    client: Client<'a>,
+    subscriber: RefCell<Box<dyn Subscriber + 'a>>,
+    filters: Vec<Box<dyn TransactionPayloadFilter>>,
+    validators: Vec<Box<dyn PayloadValidator + 'a>>,
+    spool_directory: PathBuf,
+    message_types: BTreeMap<String, String>,
+    agent_id: Option<String>,
+    timestamp_unit: Duration,
+    batch_size: usize,
+    flush_interval: Duration,
+    max_buffered: usize,
+    duration: Option<Duration>
+}
+
+impl<'a> RecordCommand<'a> {
+    pub fn new(client: Client<'a>, subscriber: Box<dyn Subscriber + 'a>, filters: Vec<Box<dyn TransactionPayloadFilter>>,
+               validators: Vec<Box<dyn PayloadValidator + 'a>>, spool_directory: &Path) -> Self {
+        RecordCommand {
+            client,
+            subscriber: RefCell::new(subscriber),
+            filters,
+            validators,
+            spool_directory: spool_directory.to_path_buf(),
+            message_types: BTreeMap::new(),
+            agent_id: None,
+            timestamp_unit: Duration::from_millis(1),
+            batch_size: 100,
+            flush_interval: Duration::from_secs(1),
+            max_buffered: 100000,
+            duration: None
+        }
+    }
+
+    /// Records the messages of a topic as this message type instead of the name of the topic.
+    pub fn with_topic(mut self, message_type: &str, topic: &str) -> Self {
+        self.message_types.insert(topic.to_string(), message_type.to_string());
+        self
+    }
+
+    /// Agent ID of messages that do not name their sender, these are dropped otherwise.
+    pub fn with_agent_id(mut self, agent_id: &str) -> Self {
+        self.agent_id = Some(agent_id.to_string());
+        self
+    }
+
+    /// Unit of the timestamps the messages are recorded with.
+    pub fn with_timestamp_unit(mut self, timestamp_unit: Duration) -> Self {
+        self.timestamp_unit = timestamp_unit;
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Longest time a message waits for its batch to fill up.
+    pub fn with_flush_interval(mut self, flush_interval: Duration) -> Self {
+        self.flush_interval = flush_interval;
+        self
+    }
+
+    /// Stops receiving while this many messages are buffered, the subscription drops what exceeds its high water mark.
+    pub fn with_max_buffered(mut self, max_buffered: usize) -> Self {
+        self.max_buffered = max_buffered;
+        self
+    }
+
+    /// Stops recording after this time, records until the process is stopped otherwise.
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    fn payload_of(&self, topic: &str, message: Vec<u8>) -> Option<TransactionPayload> {
+        let message_type = self.message_types.get(topic).map_or(topic, |message_type| message_type.as_str());
+        let agent_id = alica::decode_message(message_type, &message).as_ref()
+            .and_then(capnp::sender_id_of)
+            .or_else(|| self.agent_id.clone())?;
+        let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let timestamp = (since_epoch.as_nanos() / self.timestamp_unit.as_nanos()) as u64;
+        let payload = TransactionPayload::new(&agent_id, message_type, &message, timestamp);
+        // A single message the validator can not accept would make its whole batch invalid.
+        if self.validators.iter().all(|validator| validator.validate(&payload).is_ok()) {
+            Some(payload)
+        } else {
+            None
+        }
+    }
+
+    /// Submits the oldest buffered messages as one batch and returns how many were submitted.
+    fn submit(&self, spool: &mut Spool) -> Result<usize, sawtooth::Error> {
+        let messages = spool.oldest(self.batch_size);
+        let count = messages.len();
+        self.client.create_batch(&messages)?;
+        Ok(count)
+    }
+}
+
+impl<'a> SawtoothCommand for RecordCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        let mut spool = Spool::open(&self.spool_directory)?;
+        if spool.len() > 0 {
+            println!("Resuming with {} buffered messages from {}", spool.len(), self.spool_directory.display());
+        }
+
+        let mut subscriber = self.subscriber.borrow_mut();
+        let started_at = Instant::now();
+        let stop_at = self.duration.map(|duration| started_at + duration);
+        let mut next_flush = started_at + self.flush_interval;
+        let mut retry_at = started_at;
+        let mut backoff = INITIAL_BACKOFF;
+        let (mut received, mut submitted, mut dropped, mut rejected) = (0, 0, 0, 0);
+        loop {
+            let now = Instant::now();
+            if stop_at.map_or(false, |stop_at| now >= stop_at) {
+                break;
+            }
+            if spool.len() == 0 {
+                next_flush = now + self.flush_interval;
+            }
+
+            let submit_at = if spool.len() >= self.batch_size { retry_at } else { next_flush.max(retry_at) };
+            if spool.len() > 0 && now >= submit_at {
+                match self.submit(&mut spool) {
+                    Ok(count) => {
+                        spool.acknowledge(count)?;
+                        submitted += count;
+                        backoff = INITIAL_BACKOFF;
+                    },
+                    Err(sawtooth::Error::InvalidBatch) => {
+                        // Submitting the same messages again would block the recording for good.
+                        let count = self.batch_size.min(spool.len());
+                        eprintln!("Validator rejected a batch as invalid, dropping its {} messages", count);
+                        spool.acknowledge(count)?;
+                        rejected += count;
+                    },
+                    Err(error) => {
+                        let command::Error::ExecutionError(reason) = command::Error::from(error);
+                        eprintln!("Submitting failed, retrying in {} ms: {}", backoff.as_millis(), reason);
+                        retry_at = Instant::now() + backoff;
+                        backoff = (backoff * 2).min(MAX_BACKOFF);
+                    }
+                }
+                next_flush = Instant::now() + self.flush_interval;
+                continue;
+            }
+
+            let wake_at = stop_at.map_or(submit_at, |stop_at| stop_at.min(submit_at));
+            let timeout = wake_at.saturating_duration_since(now);
+            if spool.len() >= self.max_buffered {
+                thread::sleep(timeout);
+                continue;
+            }
+            let (topic, message) = match subscriber.receive(timeout)
+                .map_err(|error| command::Error::ExecutionError(format!("Could not receive messages: {}", error)))? {
+                Some(received_message) => received_message,
+                None => continue
+            };
+
+            received += 1;
+            match self.payload_of(&topic, message).and_then(|payload| filter::apply_all(&self.filters, payload)) {
+                Some(payload) => spool.push(payload)?,
+                None => dropped += 1
+            }
+        }
+
+        // Whatever is left stays in the spool and is submitted by the next recording.
+        while spool.len() > 0 {
+            match self.submit(&mut spool) {
+                Ok(count) => {
+                    spool.acknowledge(count)?;
+                    submitted += count;
+                },
+                Err(_) => break
+            }
+        }
+
+        println!("Received {} messages, submitted {}, dropped {} without sender, invalid or filtered out, {} rejected by the validator",
+                 received, submitted, dropped, rejected);
+        if spool.len() > 0 {
+            println!("{} messages remain buffered in {}", spool.len(), self.spool_directory.display());
+        }
+        Ok(())
+    }
+}
+
+fn spool_line_of(payload: &TransactionPayload) -> String {
+    json!({
+        "agent_id": payload.agent_id,
+        "message_type": payload.message_type,
+        "timestamp": payload.timestamp,
+        "message_hex": data_encoding::HEXLOWER.encode(&payload.message_bytes)
+    }).to_string() + "\n"
+}
+
+fn spool_error(directory: &Path, error: &std::io::Error) -> command::Error {
+    command::Error::ExecutionError(format!("Could not use the spool in {}: {}", directory.display(), error))
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+    use std::fs;
+    use sawtooth_alica_payload::payloads::TransactionPayload;
+    use crate::command::record::{Spool, COMPACTION_THRESHOLD, MESSAGES_FILE_NAME};
+
+    #[test]
+    fn it_keeps_unacknowledged_messages_in_the_spool_across_restarts() {
+        let directory = env::temp_dir().join(format!("spool-{}", uuid::Uuid::new_v4()));
+        let mut spool = Spool::open(&directory).unwrap();
+        spool.push(TransactionPayload::new("agent1", "SyncTalk", &[1], 10)).unwrap();
+        spool.push(TransactionPayload::new("agent2", "SyncTalk", &[2], 20)).unwrap();
+        spool.acknowledge(1).unwrap();
+        drop(spool);
+
+        let spool = Spool::open(&directory).unwrap();
+        let oldest: Vec<(String, u64)> = spool.oldest(10).iter()
+            .map(|payload| (payload.agent_id.clone(), payload.timestamp))
+            .collect();
+
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(oldest, vec![("agent2".to_string(), 20)]);
+    }
+
+    #[test]
+    fn it_compacts_the_spool_while_messages_are_pending() {
+        let directory = env::temp_dir().join(format!("spool-{}", uuid::Uuid::new_v4()));
+        let mut spool = Spool::open(&directory).unwrap();
+        spool.push(TransactionPayload::new("agent1", "SyncTalk", &[0], 0)).unwrap();
+        let mut largest_file = 0;
+        for timestamp in 1..5000 {
+            spool.push(TransactionPayload::new("agent1", "SyncTalk", &[1], timestamp)).unwrap();
+            spool.acknowledge(1).unwrap();
+            largest_file = largest_file.max(fs::metadata(directory.join(MESSAGES_FILE_NAME)).unwrap().len());
+        }
+        drop(spool);
+
+        let spool = Spool::open(&directory).unwrap();
+        let oldest: Vec<u64> = spool.oldest(10).iter().map(|payload| payload.timestamp).collect();
+
+        fs::remove_dir_all(&directory).unwrap();
+        assert!(largest_file < 2 * COMPACTION_THRESHOLD);
+        assert_eq!(oldest, vec![4999]);
+    }
+}
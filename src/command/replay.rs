@@ -0,0 +1,261 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+use sawtooth_alica_payload::payloads::{self, TransactionPayload};
+use serde_json::Value;
+use crate::alica::capnzero::Publisher;
+use crate::sawtooth::Client;
+use crate::command::{self, SawtoothCommand, ExecutionResult};
+use crate::filter::{self, TransactionPayloadFilter};
+
+pub enum ReplaySource<'a> {
+    /// Messages recorded in the namespace of the family.
+    Network(Client<'a>, String, &'a dyn payloads::Format),
+    /// The messages.jsonl file of an export.
+    Export(PathBuf)
+}
+
+/// Message types without an endpoint are not replayed, the topic defaults to the message type.
+pub struct Routes {
+    default_endpoint: Option<String>,
+    endpoints: Vec<(String, String)>,
+    topics: Vec<(String, String)>
+}
+
+impl Routes {
+    pub fn new(default_endpoint: Option<&str>) -> Self {
+        Routes {
+            default_endpoint: default_endpoint.map(|endpoint| endpoint.to_string()),
+            endpoints: Vec::new(),
+            topics: Vec::new()
+        }
+    }
+
+    pub fn with_endpoint(mut self, message_type: &str, endpoint: &str) -> Self {
+        self.endpoints.push((message_type.to_string(), endpoint.to_string()));
+        self
+    }
+
+    pub fn with_topic(mut self, message_type: &str, topic: &str) -> Self {
+        self.topics.push((message_type.to_string(), topic.to_string()));
+        self
+    }
+
+    /// Every endpoint a message may be published on.
+    pub fn endpoints(&self) -> Vec<&str> {
+        let mut endpoints: Vec<&str> = self.default_endpoint.iter()
+            .chain(self.endpoints.iter().map(|(_, endpoint)| endpoint))
+            .map(|endpoint| endpoint.as_str())
+            .collect();
+        endpoints.sort();
+        endpoints.dedup();
+        endpoints
+    }
+
+    fn route_of<'r>(&'r self, message_type: &'r str) -> Option<(&'r str, &'r str)> {
+        let endpoint = self.endpoints.iter()
+            .rev()
+            .find(|(routed_type, _)| routed_type == message_type)
+            .map(|(_, endpoint)| endpoint)
+            .or(self.default_endpoint.as_ref())?;
+        let topic = self.topics.iter()
+            .rev()
+            .find(|(routed_type, _)| routed_type == message_type)
+            .map_or(message_type, |(_, topic)| topic.as_str());
+        Some((endpoint, topic))
+    }
+}
+
+/// Publishes recorded messages in the order and at the pace they were sent.
+pub struct ReplayCommand<'a> {
+    source: ReplaySource<'a>,
+    filters: Vec<Box<dyn TransactionPayloadFilter>>,
+    routes: Routes,
+    publisher: RefCell<Box<dyn Publisher + 'a>>,
+    speed: f64,
+    start_offset: Duration,
+    timestamp_unit: Duration,
+    join_delay: Duration,
+    looping: bool
+}
+
+impl<'a> ReplayCommand<'a> {
+    pub fn new(source: ReplaySource<'a>, filters: Vec<Box<dyn TransactionPayloadFilter>>, routes: Routes,
+               publisher: Box<dyn Publisher + 'a>) -> Self {
+        ReplayCommand {
+            source,
+            filters,
+            routes,
+            publisher: RefCell::new(publisher),
+            speed: 1.0,
+            start_offset: Duration::from_secs(0),
+            timestamp_unit: Duration::from_millis(1),
+            join_delay: Duration::from_secs(0),
+            looping: false
+        }
+    }
+
+    /// Replays faster with a factor above 1 and slower below.
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Skips the messages sent within this time after the first message.
+    pub fn with_start_offset(mut self, start_offset: Duration) -> Self {
+        self.start_offset = start_offset;
+        self
+    }
+
+    /// Time that a difference of one between two timestamps stands for.
+    pub fn with_timestamp_unit(mut self, timestamp_unit: Duration) -> Self {
+        self.timestamp_unit = timestamp_unit;
+        self
+    }
+
+    /// Subscribers miss everything that a PUB socket sends before they are connected.
+    pub fn with_join_delay(mut self, join_delay: Duration) -> Self {
+        self.join_delay = join_delay;
+        self
+    }
+
+    /// Starts over after the last message until the process is stopped.
+    pub fn with_loop(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    fn load(&self) -> Result<Vec<TransactionPayload>, command::Error> {
+        match &self.source {
+            ReplaySource::Network(client, namespace, payload_format) => {
+                let state_entries = client.list_state_entries().map_err(|error| command::Error::from(error))?;
+                state_entries.iter()
+                    .filter(|entry| entry.get_address().starts_with(namespace.as_str()))
+                    .map(|entry| payload_format.deserialize(entry.get_data()).map_err(|error| command::Error::from(error)))
+                    .collect()
+            },
+            ReplaySource::Export(path) => {
+                let contents = fs::read_to_string(path)
+                    .map_err(|error| command::Error::ExecutionError(format!("Could not read {}: {}", path.display(), error)))?;
+                contents.lines()
+                    .enumerate()
+                    .filter(|(_, line)| !line.trim().is_empty())
+                    .map(|(index, line)| payload_of_export_line(line)
+                        .map_err(|reason| command::Error::ExecutionError(format!("Line {} of {}: {}", index + 1, path.display(), reason))))
+                    .collect()
+            }
+        }
+    }
+}
+
+impl<'a> SawtoothCommand for ReplayCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        let mut messages: Vec<TransactionPayload> = self.load()?.into_iter()
+            .filter_map(|payload| filter::apply_all(&self.filters, payload))
+            .collect();
+        messages.sort_by_key(|payload| payload.timestamp);
+        let timestamps: Vec<u64> = messages.iter().map(|payload| payload.timestamp).collect();
+        let schedule = schedule(&timestamps, self.timestamp_unit, self.start_offset, self.speed)?;
+        if schedule.is_empty() {
+            println!("No messages to replay");
+            return Ok(());
+        }
+        if schedule.iter().all(|(index, _)| self.routes.route_of(&messages[*index].message_type).is_none()) {
+            return Err(command::Error::ExecutionError(
+                format!("None of the {} messages to replay has an endpoint to publish on", schedule.len())));
+        }
+
+        thread::sleep(self.join_delay);
+        let mut publisher = self.publisher.borrow_mut();
+        loop {
+            let started_at = Instant::now();
+            let mut unrouted_messages = 0;
+            for (index, delay) in &schedule {
+                let message = &messages[*index];
+                let (endpoint, topic) = match self.routes.route_of(&message.message_type) {
+                    Some(route) => route,
+                    None => {
+                        unrouted_messages += 1;
+                        continue;
+                    }
+                };
+
+                thread::sleep(delay.saturating_sub(started_at.elapsed()));
+                publisher.publish(endpoint, topic, &message.message_bytes)
+                    .map_err(|error| command::Error::ExecutionError(format!("Could not publish on {}: {}", endpoint, error)))?;
+            }
+
+            println!("Replayed {} messages, skipped {} without an endpoint", schedule.len() - unrouted_messages,
+                     unrouted_messages);
+            if !self.looping {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Delays of the messages after the start offset from the start of the replay, for sorted timestamps.
+fn schedule(timestamps: &[u64], timestamp_unit: Duration, start_offset: Duration, speed: f64)
+            -> Result<Vec<(usize, Duration)>, command::Error> {
+    let first_timestamp = match timestamps.first() {
+        Some(first_timestamp) => *first_timestamp,
+        None => return Ok(Vec::new())
+    };
+
+    let mut schedule = Vec::new();
+    for (index, timestamp) in timestamps.iter().enumerate() {
+        let elapsed = (timestamp - first_timestamp) as u128 * timestamp_unit.as_nanos();
+        let since_start = match elapsed.checked_sub(start_offset.as_nanos()) {
+            Some(since_start) => since_start,
+            None => continue
+        };
+        let delay = Duration::try_from_secs_f64(since_start as f64 / 1e9 / speed)
+            .map_err(|_| command::Error::ExecutionError(format!("Speed {} delays the messages for too long", speed)))?;
+        schedule.push((index, delay));
+    }
+    Ok(schedule)
+}
+
+pub(crate) fn payload_of_export_line(line: &str) -> Result<TransactionPayload, String> {
+    let json: Value = serde_json::from_str(line).map_err(|error| error.to_string())?;
+    let text_field = |name: &str| json.get(name).and_then(|value| value.as_str())
+        .ok_or_else(|| format!("Field \"{}\" is missing or not a string", name));
+    let message_bytes = data_encoding::HEXLOWER.decode(text_field("message_hex")?.as_bytes())
+        .map_err(|_| "Field \"message_hex\" is not hex encoded".to_string())?;
+    let timestamp = json.get("timestamp").and_then(|timestamp| timestamp.as_u64())
+        .ok_or_else(|| "Field \"timestamp\" is missing or not an unsigned integer".to_string())?;
+
+    Ok(TransactionPayload::new(text_field("agent_id")?, text_field("message_type")?, &message_bytes, timestamp))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+    use crate::command::replay::{schedule, Routes};
+
+    #[test]
+    fn it_schedules_messages_after_the_start_offset_at_the_given_speed() {
+        let schedule = schedule(&[100, 150, 300, 500], Duration::from_millis(1), Duration::from_millis(100), 2.0)
+            .expect("Messages were not scheduled");
+
+        assert_eq!(schedule, vec![(2, Duration::from_millis(50)), (3, Duration::from_millis(150))]);
+    }
+
+    #[test]
+    fn it_rejects_speeds_that_delay_messages_beyond_a_duration() {
+        assert!(schedule(&[0, 1000], Duration::from_millis(1), Duration::from_millis(0), 1e-300).is_err());
+    }
+
+    #[test]
+    fn it_routes_message_types_to_their_endpoint_and_topic() {
+        let routes = Routes::new(Some("tcp://*:5555"))
+            .with_endpoint("SyncTalk", "tcp://*:5556")
+            .with_topic("AlicaEngineInfo", "EngineInfo");
+
+        assert_eq!(routes.route_of("SyncTalk"), Some(("tcp://*:5556", "SyncTalk")));
+        assert_eq!(routes.route_of("AlicaEngineInfo"), Some(("tcp://*:5555", "EngineInfo")));
+        assert_eq!(Routes::new(None).route_of("SyncTalk"), None);
+    }
+}
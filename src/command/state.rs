@@ -1,51 +1,419 @@
 use crate::sawtooth::Client;
 use crate::command::{self, SawtoothCommand, ExecutionResult};
-use sawtooth_alica_payload::payloads;
+use crate::command::address;
+use sawtooth_sdk::messages::client_state::ClientStateListResponse_Entry;
+use sawtooth_sdk::messages::block::BlockHeader;
+use sawtooth_alica_payload::{payloads, TransactionFamily};
 use sawtooth_alica_payload::payloads::TransactionPayload;
-use crate::filter::TransactionPayloadFilter;
+use crate::filter::{self, TransactionPayloadFilter};
+use crate::format::FormatRegistry;
+use crate::output::{self, OutputFormat};
+use std::collections::BTreeMap;
+use serde_json::{json, Value};
+
+enum PayloadDecoding<'a> {
+    Fixed(&'a dyn payloads::Format),
+    Detected(&'a FormatRegistry)
+}
+
+enum IntegrityViolation {
+    MalformedAddress,
+    Undecodable(String),
+    AddressMismatch(String)
+}
+
+impl IntegrityViolation {
+    fn description(&self) -> String {
+        match self {
+            IntegrityViolation::MalformedAddress => "Address is not a valid state address".to_string(),
+            IntegrityViolation::Undecodable(reason) => format!("Data is not a transaction payload: {}", reason),
+            IntegrityViolation::AddressMismatch(expected_address) =>
+                format!("Payload belongs to address {}", expected_address)
+        }
+    }
+}
 
 pub struct ListCommand<'a> {
     client: Client<'a>,
-    payload_format: &'a dyn payloads::Format,
+    payload_decoding: PayloadDecoding<'a>,
     namespace: String,
-    filters: Vec<Box<dyn TransactionPayloadFilter>>
+    filters: Vec<Box<dyn TransactionPayloadFilter>>,
+    output_format: OutputFormat,
+    integrity_check: Option<&'a TransactionFamily>,
+    block: Option<String>
 }
 
 impl<'a> ListCommand<'a> {
     pub fn new(client: Client<'a>, namespace: &str, payload_format: &'a dyn payloads::Format, filters: Vec<Box<dyn TransactionPayloadFilter>>) -> Self {
         ListCommand {
             client,
-            payload_format,
+            payload_decoding: PayloadDecoding::Fixed(payload_format),
+            namespace: namespace.to_string(),
+            filters,
+            output_format: OutputFormat::Text,
+            integrity_check: None,
+            block: None
+        }
+    }
+
+    pub fn with_format_detection(client: Client<'a>, namespace: &str, format_registry: &'a FormatRegistry,
+                                 filters: Vec<Box<dyn TransactionPayloadFilter>>) -> Self {
+        ListCommand {
+            client,
+            payload_decoding: PayloadDecoding::Detected(format_registry),
             namespace: namespace.to_string(),
-            filters
+            filters,
+            output_format: OutputFormat::Text,
+            integrity_check: None,
+            block: None
+        }
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<(TransactionPayload, Option<String>), command::Error> {
+        match self.payload_decoding {
+            PayloadDecoding::Fixed(payload_format) => payload_format.deserialize(data)
+                .map(|payload| (payload, None))
+                .map_err(|error| command::Error::from(error)),
+            PayloadDecoding::Detected(format_registry) => format_registry.detect(data)
+                .map(|(format_name, payload)| (payload, Some(format_name.to_string())))
+                .ok_or(command::Error::ExecutionError(
+                    format!("None of the formats {} could decode the entry", format_registry.names().join(", "))))
+        }
+    }
+
+    pub fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    /// Lists the entries as they were after the block with the ID or number instead of the current ones.
+    pub fn with_block(mut self, block: &str) -> Self {
+        self.block = Some(block.to_string());
+        self
+    }
+
+    /// Reports undecodable or misplaced entries instead of aborting on the first undecodable one.
+    pub fn with_integrity_check(mut self, transaction_family: &'a TransactionFamily) -> Self {
+        self.integrity_check = Some(transaction_family);
+        self
+    }
+
+    fn check_integrity(&self, transaction_family: &TransactionFamily, entry: &ClientStateListResponse_Entry)
+                       -> Result<(TransactionPayload, Option<String>), IntegrityViolation> {
+        if address::owner_of(transaction_family, entry.get_address()).is_err() {
+            return Err(IntegrityViolation::MalformedAddress);
         }
+
+        let (payload, format_name) = self.decode(entry.get_data())
+            .map_err(|error| match error {
+                command::Error::ExecutionError(reason) => IntegrityViolation::Undecodable(reason)
+            })?;
+        let expected_address = transaction_family.calculate_state_address_for(&payload);
+        if expected_address != entry.get_address() {
+            return Err(IntegrityViolation::AddressMismatch(expected_address));
+        }
+
+        Ok((payload, format_name))
     }
 }
 
 impl<'a> SawtoothCommand for ListCommand<'a> {
     fn execute(&self) -> ExecutionResult {
-        let state_entries = self.client.list_state_entries().map_err(|error| command::Error::from(error))?;
+        let state_entries = match &self.block {
+            Some(block) => {
+                let state_root = state_root_at(&self.client, block)?;
+                self.client.list_state_entries_at(&state_root)
+            },
+            None => self.client.list_state_entries()
+        }.map_err(|error| command::Error::from(error))?;
+
+        if self.output_format == OutputFormat::Text {
+            println!("Got {} state entries", state_entries.len());
+        }
+
+        let mut decoded_payloads = Vec::new();
+        let mut integrity_violations = Vec::new();
+        for entry in state_entries.iter().filter(|entry| entry.get_address().starts_with(&self.namespace)) {
+            match self.integrity_check {
+                Some(transaction_family) => match self.check_integrity(transaction_family, entry) {
+                    Ok(decoded_payload) => decoded_payloads.push(decoded_payload),
+                    Err(violation) => integrity_violations.push((entry.get_address().to_string(), violation))
+                },
+                None => decoded_payloads.push(self.decode(entry.get_data())?)
+            }
+        }
+
+        let payloads = decoded_payloads.into_iter()
+            .filter_map(|(payload, format_name)| filter::apply_all(&self.filters, payload).map(|payload| (payload, format_name)));
+
+        match self.output_format {
+            OutputFormat::Text => for (payload, format_name) in payloads {
+                println!("Transaction:");
+                output::print_payload(&payload);
+                if let Some(format_name) = format_name {
+                    println!("-> Payload Format: \"{}\"", format_name)
+                }
+            },
+            OutputFormat::Json => output::print_json(payloads
+                .map(|(payload, format_name)| {
+                    let mut json_payload = output::payload_as_json(&payload);
+                    if let Some(format_name) = format_name {
+                        json_payload.insert("payload_format".to_string(), Value::from(format_name));
+                    }
+                    Value::Object(json_payload)
+                })
+                .collect())
+        }
+
+        report_integrity_violations(&integrity_violations)
+    }
+}
+
+pub struct GetCommand<'a> {
+    client: Client<'a>,
+    address: String,
+    payload_format: &'a dyn payloads::Format,
+    output_format: OutputFormat,
+    block: Option<String>
+}
+
+impl<'a> GetCommand<'a> {
+    pub fn new(client: Client<'a>, address: &str, payload_format: &'a dyn payloads::Format) -> Self {
+        GetCommand {
+            client,
+            address: address.to_string(),
+            payload_format,
+            output_format: OutputFormat::Text,
+            block: None
+        }
+    }
+
+    pub fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    /// Reads the entry as it was after the block with the ID or number instead of the current one.
+    pub fn with_block(mut self, block: &str) -> Self {
+        self.block = Some(block.to_string());
+        self
+    }
+
+    pub fn read_entry(&self) -> Result<TransactionPayload, command::Error> {
+        let state_root = match &self.block {
+            Some(block) => state_root_at(&self.client, block)?,
+            None => String::new()
+        };
+        let data = match self.client.get_state_at(&self.address, &state_root).map_err(|error| command::Error::from(error))? {
+            Some(data) => data,
+            None => return Err(command::Error::ExecutionError(format!("There is no state entry at {}", self.address)))
+        };
+        self.payload_format.deserialize(&data).map_err(|error| command::Error::from(error))
+    }
+}
+
+impl<'a> SawtoothCommand for GetCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        let payload = self.read_entry()?;
+
+        match self.output_format {
+            OutputFormat::Text => {
+                println!("State entry at {}:", self.address);
+                output::print_payload(&payload);
+            },
+            OutputFormat::Json => {
+                let mut json_payload = output::payload_as_json(&payload);
+                json_payload.insert("address".to_string(), Value::from(self.address.clone()));
+                output::print_json_value(&Value::Object(json_payload));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How a state entry differs between two blocks, with the data before and after.
+pub enum EntryChange {
+    Added(Vec<u8>),
+    Changed(Vec<u8>, Vec<u8>),
+    Removed(Vec<u8>)
+}
+
+impl EntryChange {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EntryChange::Added(_) => "added",
+            EntryChange::Changed(_, _) => "changed",
+            EntryChange::Removed(_) => "removed"
+        }
+    }
+
+    fn before(&self) -> Option<&[u8]> {
+        match self {
+            EntryChange::Added(_) => None,
+            EntryChange::Changed(before, _) | EntryChange::Removed(before) => Some(before)
+        }
+    }
+
+    fn after(&self) -> Option<&[u8]> {
+        match self {
+            EntryChange::Added(after) | EntryChange::Changed(_, after) => Some(after),
+            EntryChange::Removed(_) => None
+        }
+    }
+}
+
+/// Lists the entries of the namespace that were added, changed or removed between two blocks.
+pub struct DiffCommand<'a> {
+    client: Client<'a>,
+    namespace: String,
+    payload_format: &'a dyn payloads::Format,
+    filters: Vec<Box<dyn TransactionPayloadFilter>>,
+    from_block: String,
+    to_block: String,
+    output_format: OutputFormat
+}
+
+impl<'a> DiffCommand<'a> {
+    pub fn new(client: Client<'a>, namespace: &str, payload_format: &'a dyn payloads::Format,
+               filters: Vec<Box<dyn TransactionPayloadFilter>>, from_block: &str, to_block: &str) -> Self {
+        DiffCommand {
+            client,
+            namespace: namespace.to_string(),
+            payload_format,
+            filters,
+            from_block: from_block.to_string(),
+            to_block: to_block.to_string(),
+            output_format: OutputFormat::Text
+        }
+    }
 
-        println!("Got {} state entries", state_entries.len());
+    pub fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
 
-        let mut payloads: Vec<TransactionPayload> = state_entries.iter()
+    fn entries_at(&self, block: &str) -> Result<BTreeMap<String, Vec<u8>>, command::Error> {
+        let state_root = state_root_at(&self.client, block)?;
+        let state_entries = self.client.list_state_entries_at(&state_root).map_err(|error| command::Error::from(error))?;
+        Ok(state_entries.into_iter()
             .filter(|entry| entry.get_address().starts_with(&self.namespace))
-            .map(|entry| self.payload_format.deserialize(entry.get_data()).map_err(|error| command::Error::from(error)))
-            .collect::<Result<Vec<TransactionPayload>, command::Error>>()?;
+            .map(|mut entry| (entry.take_address(), entry.take_data()))
+            .collect())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<TransactionPayload, command::Error> {
+        self.payload_format.deserialize(data).map_err(|error| command::Error::from(error))
+    }
+
+    /// A change passes the filters if the entry passes them before or after the change.
+    fn passes_filters(&self, change: &EntryChange) -> Result<bool, command::Error> {
+        for data in change.before().into_iter().chain(change.after()) {
+            if filter::apply_all(&self.filters, self.decode(data)?).is_some() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn payload_as_json(&self, data: Option<&[u8]>) -> Result<Value, command::Error> {
+        match data {
+            Some(data) => Ok(Value::Object(output::payload_as_json(&self.decode(data)?))),
+            None => Ok(Value::Null)
+        }
+    }
+
+    /// Changes between the two blocks that pass the filters, ordered by address.
+    pub fn changes(&self) -> Result<Vec<(String, EntryChange)>, command::Error> {
+        let mut before = self.entries_at(&self.from_block)?;
+        let after = self.entries_at(&self.to_block)?;
 
-        for filter in &self.filters {
-            filter.filter(&mut payloads);
+        let mut changes = BTreeMap::new();
+        for (address, data) in after {
+            match before.remove(&address) {
+                Some(previous_data) if previous_data == data => {},
+                Some(previous_data) => { changes.insert(address, EntryChange::Changed(previous_data, data)); },
+                None => { changes.insert(address, EntryChange::Added(data)); }
+            }
+        }
+        for (address, data) in before {
+            changes.insert(address, EntryChange::Removed(data));
         }
 
-        for payload in payloads {
-            let transaction = payload;
-            println!("Transaction:");
-            println!("-> Agent ID: \"{}\"", &transaction.agent_id);
-            println!("-> Message Type: \"{}\"", &transaction.message_type);
-            println!("-> Message: \"{}\"", String::from_utf8(transaction.message_bytes.clone()).expect("Message is not a string"));
-            println!("-> Timestamp of sending: \"{}\"", transaction.timestamp)
+        let mut filtered_changes = Vec::new();
+        for (address, change) in changes {
+            if self.passes_filters(&change)? {
+                filtered_changes.push((address, change));
+            }
         }
+        Ok(filtered_changes)
+    }
+}
 
+impl<'a> SawtoothCommand for DiffCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        let filtered_changes = self.changes()?;
+
+        match self.output_format {
+            OutputFormat::Text => {
+                let count = |name: &str| filtered_changes.iter().filter(|(_, change)| change.name() == name).count();
+                println!("{} entries added, {} changed and {} removed between block {} and block {}",
+                         count("added"), count("changed"), count("removed"), self.from_block, self.to_block);
+                for (address, change) in &filtered_changes {
+                    match change {
+                        EntryChange::Added(after) => {
+                            println!("Added entry at {}:", address);
+                            output::print_payload(&self.decode(after)?);
+                        },
+                        EntryChange::Changed(before, after) => {
+                            println!("Changed entry at {} from:", address);
+                            output::print_payload(&self.decode(before)?);
+                            println!("to:");
+                            output::print_payload(&self.decode(after)?);
+                        },
+                        EntryChange::Removed(before) => {
+                            println!("Removed entry at {}:", address);
+                            output::print_payload(&self.decode(before)?);
+                        }
+                    }
+                }
+            },
+            OutputFormat::Json => {
+                let mut json_changes = Vec::new();
+                for (address, change) in &filtered_changes {
+                    json_changes.push(json!({
+                        "address": address,
+                        "change": change.name(),
+                        "before": self.payload_as_json(change.before())?,
+                        "after": self.payload_as_json(change.after())?
+                    }));
+                }
+                output::print_json(json_changes);
+            }
+        }
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Resolves a block ID or number to the state root the state had after the block.
+fn state_root_at(client: &Client, block: &str) -> Result<String, command::Error> {
+    let block = match client.get_block(block).map_err(|error| command::Error::from(error))? {
+        Some(block) => block,
+        None => return Err(command::Error::ExecutionError(format!("There is no block {}", block)))
+    };
+    let header = protobuf::parse_from_bytes::<BlockHeader>(block.get_header())
+        .map_err(|_| command::Error::ExecutionError(format!("Invalid header in block {}", block.get_header_signature())))?;
+    Ok(header.get_state_root_hash().to_string())
+}
+
+fn report_integrity_violations(integrity_violations: &[(String, IntegrityViolation)]) -> ExecutionResult {
+    if integrity_violations.is_empty() {
+        return Ok(());
+    }
+
+    for (address, violation) in integrity_violations {
+        eprintln!("Integrity violation at {}:", address);
+        eprintln!("-> {}", violation.description());
+    }
+    Err(command::Error::ExecutionError(format!("{} state entries violate the integrity of the namespace",
+                                               integrity_violations.len())))
+}
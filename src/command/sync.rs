@@ -0,0 +1,120 @@
+use sawtooth_sdk::messages::block::{Block, BlockHeader};
+use sawtooth_sdk::messages::transaction::{Transaction, TransactionHeader};
+use sawtooth_sdk::messages::transaction_receipt::StateChange;
+use crate::sawtooth::Client;
+use crate::sawtooth::transport::mirror::{Mirror, MirroredBlock};
+use crate::command::{self, SawtoothCommand, ExecutionResult};
+
+const BLOCK_PAGE_SIZE: i32 = 100;
+const RECEIPTS_PER_REQUEST: usize = 50;
+
+/// Copies the blocks committed since the last sync into the mirror.
+pub struct SyncCommand<'a> {
+    client: Client<'a>,
+    mirror: Mirror,
+    family_name: String,
+    namespace: String
+}
+
+impl<'a> SyncCommand<'a> {
+    pub fn new(client: Client<'a>, mirror: Mirror, family_name: &str, namespace: &str) -> Self {
+        SyncCommand {
+            client,
+            mirror,
+            family_name: family_name.to_string(),
+            namespace: namespace.to_string()
+        }
+    }
+
+    /// Returns the blocks after the mirror head, the oldest first.
+    fn new_blocks(&self) -> Result<Vec<Block>, command::Error> {
+        let head = self.mirror.head();
+        let mut blocks = Vec::new();
+        let mut start: Option<String> = None;
+        loop {
+            let (page, next) = self.client.list_blocks(start.as_deref(), BLOCK_PAGE_SIZE)
+                .map_err(|error| command::Error::from(error))?;
+            for block in page {
+                if head.as_ref().map_or(false, |head| head.block_id == block.get_header_signature()) {
+                    blocks.reverse();
+                    return Ok(blocks);
+                }
+                blocks.push(block);
+            }
+
+            match next {
+                Some(next) => start = Some(next),
+                None => break
+            }
+        }
+
+        match head {
+            Some(head) => Err(command::Error::ExecutionError(format!(
+                "Block {} the mirror was synced to is no longer part of the chain, remove {} to sync from scratch",
+                head.block_id, self.mirror.path().display()))),
+            None => {
+                blocks.reverse();
+                Ok(blocks)
+            }
+        }
+    }
+
+    fn transactions_of_family(&self, blocks: &[Block]) -> Result<Vec<Transaction>, command::Error> {
+        let mut transactions = Vec::new();
+        for transaction in blocks.iter().flat_map(|block| block.get_batches()).flat_map(|batch| batch.get_transactions()) {
+            let header = protobuf::parse_from_bytes::<TransactionHeader>(transaction.get_header())
+                .map_err(|_| command::Error::ExecutionError(
+                    format!("Invalid header in transaction {}", transaction.get_header_signature())))?;
+            if header.get_family_name() == self.family_name {
+                transactions.push(transaction.clone());
+            }
+        }
+        Ok(transactions)
+    }
+
+    fn state_changes_of(&self, transactions: &[Transaction]) -> Result<Vec<StateChange>, command::Error> {
+        let transaction_ids: Vec<String> = transactions.iter()
+            .map(|transaction| transaction.get_header_signature().to_string())
+            .collect();
+
+        let mut state_changes = Vec::new();
+        for chunk in transaction_ids.chunks(RECEIPTS_PER_REQUEST) {
+            let receipts = self.client.get_receipts(chunk).map_err(|error| command::Error::from(error))?;
+            state_changes.extend(receipts.iter()
+                .flat_map(|receipt| receipt.get_state_changes())
+                .filter(|state_change| state_change.get_address().starts_with(&self.namespace))
+                .cloned());
+        }
+        Ok(state_changes)
+    }
+}
+
+impl<'a> SawtoothCommand for SyncCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        let blocks = self.new_blocks()?;
+        let newest_block = match blocks.last() {
+            Some(block) => block,
+            None => {
+                match self.mirror.head() {
+                    Some(head) => println!("Mirror is up to date at block {}", head.block_num),
+                    None => println!("Chain has no blocks yet, nothing to mirror")
+                }
+                return Ok(());
+            }
+        };
+        let header = protobuf::parse_from_bytes::<BlockHeader>(newest_block.get_header())
+            .map_err(|_| command::Error::ExecutionError(format!("Invalid header in block {}", newest_block.get_header_signature())))?;
+
+        let transactions = self.transactions_of_family(&blocks)?;
+        let state_changes = self.state_changes_of(&transactions)?;
+        let head = MirroredBlock {
+            block_num: header.get_block_num(),
+            block_id: newest_block.get_header_signature().to_string()
+        };
+        self.mirror.append(head, &transactions, &state_changes).map_err(|error| command::Error::from(error))?;
+
+        println!("Synced {} blocks with {} transactions and {} state changes, mirror is at block {}",
+                 blocks.len(), transactions.len(), state_changes.len(), header.get_block_num());
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+use std::fs;
+use std::io::{self, Write};
+use crate::sawtooth::{Client, TransactionFactory, TransactionOptions};
+use crate::command::{self, SawtoothCommand, ExecutionResult};
+use crate::validation::PayloadValidator;
+use crate::filter::{self, TransactionPayloadFilter};
+use crate::output::{self, OutputFormat};
+use sawtooth_alica_payload::payloads;
+use sawtooth_alica_payload::payloads::TransactionPayload;
+use sawtooth_sdk::messages::transaction::{TransactionHeader, TransactionList};
+use protobuf::Message;
+use serde_json::Value;
+
+pub struct ListCommand<'a> {
+    client: Client<'a>,
+    family_name: String,
+    version_formats: Vec<(String, &'a dyn payloads::Format)>,
+    filters: Vec<Box<dyn TransactionPayloadFilter>>,
+    output_format: OutputFormat
+}
+
+impl<'a> ListCommand<'a> {
+    pub fn new(client: Client<'a>, family_name: &str, version_formats: Vec<(String, &'a dyn payloads::Format)>,
+               filters: Vec<Box<dyn TransactionPayloadFilter>>) -> Self {
+        ListCommand {
+            client,
+            family_name: family_name.to_string(),
+            version_formats,
+            filters,
+            output_format: OutputFormat::Text
+        }
+    }
+
+    fn format_of(&self, family_version: &str) -> Option<&'a dyn payloads::Format> {
+        self.version_formats.iter()
+            .find(|(version, _)| version == family_version)
+            .map(|(_, format)| *format)
+    }
+
+    pub fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+}
+
+impl<'a> SawtoothCommand for ListCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        let transactions = self.client.list_transactions().map_err(|error| command::Error::from(error))?;
+
+        let mut listed_transactions = Vec::new();
+        let mut unknown_versions = 0;
+        for transaction in &transactions {
+            let header = protobuf::parse_from_bytes::<TransactionHeader>(transaction.get_header())
+                .map_err(|_| command::Error::ExecutionError(
+                    format!("Invalid header in transaction {}", transaction.get_header_signature())))?;
+            if header.get_family_name() != self.family_name {
+                continue;
+            }
+
+            let payload_format = match self.format_of(header.get_family_version()) {
+                Some(payload_format) => payload_format,
+                None => {
+                    unknown_versions += 1;
+                    continue;
+                }
+            };
+
+            let payload = payload_format.deserialize(transaction.get_payload())
+                .map_err(|error| command::Error::from(error))?;
+            if let Some(payload) = filter::apply_all(&self.filters, payload) {
+                listed_transactions.push((transaction.get_header_signature(), header, payload));
+            }
+        }
+
+        if unknown_versions > 0 && self.output_format == OutputFormat::Text {
+            let versions: Vec<&str> = self.version_formats.iter().map(|(version, _)| version.as_str()).collect();
+            println!("Skipped {} transactions of versions other than {}", unknown_versions, versions.join(", "));
+        }
+
+        match self.output_format {
+            OutputFormat::Text => for (transaction_id, header, payload) in listed_transactions {
+                println!("Transaction {}:", transaction_id);
+                println!("-> Signer: {}", header.get_signer_public_key());
+                println!("-> Family Version: {}", header.get_family_version());
+                output::print_payload(&payload);
+            },
+            OutputFormat::Json => output::print_json(listed_transactions.into_iter()
+                .map(|(transaction_id, header, payload)| {
+                    let mut json_transaction = output::payload_as_json(&payload);
+                    json_transaction.insert("transaction_id".to_string(), Value::from(transaction_id));
+                    json_transaction.insert("signer_public_key".to_string(), Value::from(header.get_signer_public_key()));
+                    json_transaction.insert("family_version".to_string(), Value::from(header.get_family_version()));
+                    Value::Object(json_transaction)
+                })
+                .collect())
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a signed transaction for a gateway holding the batcher key to submit later.
+pub struct CreateCommand<'a> {
+    factory: &'a dyn TransactionFactory,
+    message: TransactionPayload,
+    options: TransactionOptions,
+    validators: Vec<Box<dyn PayloadValidator + 'a>>,
+    output_path: String
+}
+
+impl<'a> CreateCommand<'a> {
+    /// Writes to stdout if the output path is "-".
+    pub fn new(factory: &'a dyn TransactionFactory, message: TransactionPayload,
+               validators: Vec<Box<dyn PayloadValidator + 'a>>, output_path: &str) -> Self {
+        CreateCommand {
+            factory,
+            message,
+            options: TransactionOptions::default(),
+            validators,
+            output_path: output_path.to_string()
+        }
+    }
+
+    pub fn with_options(mut self, options: TransactionOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl<'a> SawtoothCommand for CreateCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        for validator in &self.validators {
+            validator.validate(&self.message).map_err(|error| command::Error::from(error))?;
+        }
+
+        let header = self.factory.create_transaction_header_with(&self.message, &self.options)
+            .map_err(|error| command::Error::from(error))?;
+        let transaction = self.factory.create_transaction_for(&self.message, &header)
+            .map_err(|error| command::Error::from(error))?;
+        let transaction_id = transaction.get_header_signature().to_string();
+
+        let mut transaction_list = TransactionList::new();
+        transaction_list.set_transactions(protobuf::RepeatedField::from_vec(vec![transaction]));
+        let contents = transaction_list.write_to_bytes()
+            .map_err(|_| command::Error::ExecutionError("Could not serialize the transaction list".to_string()))?;
+
+        let written = if self.output_path == "-" {
+            io::stdout().write_all(&contents)
+        } else {
+            fs::write(&self.output_path, contents)
+        };
+        written.map_err(|error| command::Error::ExecutionError(format!("Could not write {}: {}", self.output_path, error)))?;
+
+        if self.output_path != "-" {
+            println!("Transaction {}", transaction_id);
+        }
+        Ok(())
+    }
+}
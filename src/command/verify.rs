@@ -0,0 +1,125 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use sawtooth_sdk::messages::batch::BatchList;
+use serde_json::Value;
+use crate::sawtooth::Client;
+use crate::sawtooth::verification::{self, Violation};
+use crate::command::{self, SawtoothCommand, ExecutionResult};
+use crate::output::{self, OutputFormat};
+
+pub enum BatchSource<'a> {
+    Network(Client<'a>),
+    File(PathBuf)
+}
+
+pub struct BatchesCommand<'a> {
+    source: BatchSource<'a>,
+    output_format: OutputFormat
+}
+
+impl<'a> BatchesCommand<'a> {
+    pub fn new(source: BatchSource<'a>) -> Self {
+        BatchesCommand {
+            source,
+            output_format: OutputFormat::Text
+        }
+    }
+
+    pub fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    /// Violations found in each batch, keyed by the batch ID.
+    pub fn violations(&self) -> Result<Vec<(String, Vec<Violation>)>, command::Error> {
+        let batches = match &self.source {
+            BatchSource::Network(client) => client.list_batches().map_err(|error| command::Error::from(error))?,
+            BatchSource::File(path) => read_batch_file(path)?.take_batches().into_vec()
+        };
+
+        Ok(batches.iter()
+            .map(|batch| (batch.get_header_signature().to_string(), verification::verify_batch(batch)))
+            .collect())
+    }
+}
+
+impl<'a> SawtoothCommand for BatchesCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        report("Batch", "batches", self.violations()?, &self.output_format)
+    }
+}
+
+pub struct TransactionsCommand<'a> {
+    client: Client<'a>,
+    output_format: OutputFormat
+}
+
+impl<'a> TransactionsCommand<'a> {
+    pub fn new(client: Client<'a>) -> Self {
+        TransactionsCommand {
+            client,
+            output_format: OutputFormat::Text
+        }
+    }
+
+    pub fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+}
+
+impl<'a> SawtoothCommand for TransactionsCommand<'a> {
+    fn execute(&self) -> ExecutionResult {
+        let transactions = self.client.list_transactions().map_err(|error| command::Error::from(error))?;
+
+        let results = transactions.iter()
+            .map(|transaction| (transaction.get_header_signature().to_string(), verification::verify_transaction(transaction)))
+            .collect();
+        report("Transaction", "transactions", results, &self.output_format)
+    }
+}
+
+fn read_batch_file(path: &Path) -> Result<BatchList, command::Error> {
+    let contents = fs::read(path)
+        .map_err(|error| command::Error::ExecutionError(format!("Could not read {}: {}", path.display(), error)))?;
+    protobuf::parse_from_bytes::<BatchList>(&contents)
+        .map_err(|_| command::Error::ExecutionError(format!("{} does not contain a batch list", path.display())))
+}
+
+fn report(kind: &str, kind_plural: &str, results: Vec<(String, Vec<Violation>)>, output_format: &OutputFormat) -> ExecutionResult {
+    let failed = results.iter().filter(|(_, violations)| !violations.is_empty()).count();
+    let total = results.len();
+
+    match output_format {
+        OutputFormat::Text => {
+            for (id, violations) in &results {
+                if violations.is_empty() {
+                    println!("{} {}: OK", kind, id);
+                } else {
+                    println!("{} {}: FAILED", kind, id);
+                    for violation in violations {
+                        println!("-> {}", violation.description());
+                    }
+                }
+            }
+            println!("Verified {} {}, {} failed", total, kind_plural, failed);
+        },
+        OutputFormat::Json => output::print_json(results.iter()
+            .map(|(id, violations)| {
+                let mut result = serde_json::Map::new();
+                result.insert("id".to_string(), Value::from(id.as_str()));
+                result.insert("valid".to_string(), Value::from(violations.is_empty()));
+                result.insert("violations".to_string(), Value::from(violations.iter()
+                    .map(|violation| violation.description())
+                    .collect::<Vec<String>>()));
+                Value::Object(result)
+            })
+            .collect())
+    }
+
+    if failed > 0 {
+        Err(command::Error::ExecutionError(format!("{} of {} {} failed verification", failed, total, kind_plural)))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+use rustyline::completion::Completer;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+use crate::console::definition::CommandDefinition;
+
+/// Completes the CLI definition and the commands of the console itself.
+pub struct CommandCompletion {
+    definition: CommandDefinition,
+    console_commands: Vec<String>
+}
+
+impl CommandCompletion {
+    pub fn new(definition: CommandDefinition, console_commands: &[&str]) -> Self {
+        CommandCompletion {
+            definition,
+            console_commands: console_commands.iter().map(|command| command.to_string()).collect()
+        }
+    }
+
+    pub fn candidates_for(&self, line: &str) -> (usize, Vec<String>) {
+        let start = line.rfind(char::is_whitespace).map_or(0, |position| position + 1);
+        let prefix = &line[start..];
+        let words: Vec<String> = line[..start].split_whitespace().map(|word| word.to_string()).collect();
+        let (command, depth) = self.definition.resolve(&words);
+
+        let mut candidates = Vec::new();
+        if words.len() == depth {
+            candidates.extend(command.subcommands.iter().map(|subcommand| subcommand.name.clone()));
+        }
+        if words.is_empty() {
+            candidates.extend(self.console_commands.iter().cloned());
+        }
+        if words.first().map_or(false, |word| word == "set" || word == "unset") && words.len() == 1 {
+            candidates.extend(self.option_names_of(&self.definition));
+        }
+
+        let previous_option = words.last()
+            .and_then(|word| word.strip_prefix("--"))
+            .and_then(|long| command.options.iter().find(|option| option.long.as_deref() == Some(long)));
+        match previous_option {
+            Some(option) if !option.possible_values.is_empty() => candidates.extend(option.possible_values.iter().cloned()),
+            _ if depth > 0 => candidates.extend(command.options.iter()
+                .filter_map(|option| option.long.as_ref())
+                .map(|long| format!("--{}", long))),
+            _ => {}
+        }
+
+        candidates.retain(|candidate| candidate.starts_with(prefix));
+        candidates.sort();
+        candidates.dedup();
+        (start, candidates)
+    }
+
+    fn option_names_of(&self, command: &CommandDefinition) -> Vec<String> {
+        let mut names: Vec<String> = command.options.iter().map(|option| option.name.clone()).collect();
+        for subcommand in &command.subcommands {
+            names.extend(self.option_names_of(subcommand));
+        }
+        names
+    }
+}
+
+impl Completer for CommandCompletion {
+    type Candidate = String;
+
+    fn complete(&self, line: &str, pos: usize, _context: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates_for(&line[..pos]))
+    }
+}
+
+impl Hinter for CommandCompletion {
+    type Hint = String;
+}
+
+impl Highlighter for CommandCompletion {}
+
+impl Validator for CommandCompletion {}
+
+impl Helper for CommandCompletion {}
+
+#[cfg(test)]
+mod test {
+    use yaml_rust::YamlLoader;
+    use crate::console::completion::CommandCompletion;
+    use crate::console::definition::CommandDefinition;
+
+    const DEFINITION: &str = "
+name: client
+subcommands:
+  - state:
+      subcommands:
+        - list:
+            args:
+              - output:
+                  long: output
+                  possible_values: [ text, json ]
+  - sync:
+      about: Mirrors the chain
+";
+
+    fn completion() -> CommandCompletion {
+        let yaml = YamlLoader::load_from_str(DEFINITION).unwrap();
+        CommandCompletion::new(CommandDefinition::from_yaml("client", &yaml[0]), &["set", "exit"])
+    }
+
+    #[test]
+    fn it_completes_subcommands_and_console_commands_of_the_first_word() {
+        assert_eq!(completion().candidates_for("s"), (0, vec!["set".to_string(), "state".to_string(), "sync".to_string()]));
+    }
+
+    #[test]
+    fn it_completes_possible_values_of_the_previous_option() {
+        assert_eq!(completion().candidates_for("state list --output j"), (20, vec!["json".to_string()]));
+    }
+}
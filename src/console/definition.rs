@@ -0,0 +1,145 @@
+use yaml_rust::Yaml;
+
+/// Option of a command as declared in the CLI definition.
+#[derive(Clone, Debug)]
+pub struct OptionDefinition {
+    pub name: String,
+    pub long: Option<String>,
+    pub short: Option<String>,
+    pub possible_values: Vec<String>
+}
+
+/// Command of the CLI definition with its options and nested subcommands.
+#[derive(Clone, Debug)]
+pub struct CommandDefinition {
+    pub name: String,
+    pub options: Vec<OptionDefinition>,
+    pub subcommands: Vec<CommandDefinition>
+}
+
+impl CommandDefinition {
+    pub fn from_yaml(name: &str, yaml: &Yaml) -> Self {
+        let options = entries_of(&yaml["args"]).into_iter()
+            .map(|(name, arg)| OptionDefinition {
+                name,
+                long: arg["long"].as_str().map(|long| long.to_string()),
+                short: arg["short"].as_str().map(|short| short.to_string()),
+                possible_values: arg["possible_values"].as_vec().map(|values| values.iter()
+                        .filter_map(|value| value.as_str())
+                        .map(|value| value.to_string())
+                        .collect())
+                    .unwrap_or_default()
+            })
+            .collect();
+        let subcommands = entries_of(&yaml["subcommands"]).into_iter()
+            .map(|(name, subcommand)| CommandDefinition::from_yaml(&name, subcommand))
+            .collect();
+
+        CommandDefinition {
+            name: name.to_string(),
+            options,
+            subcommands
+        }
+    }
+
+    pub fn subcommand(&self, name: &str) -> Option<&CommandDefinition> {
+        self.subcommands.iter().find(|subcommand| subcommand.name == name)
+    }
+
+    /// Returns the innermost subcommand the leading words name and the number of words consumed.
+    pub fn resolve(&self, words: &[String]) -> (&CommandDefinition, usize) {
+        let mut command = self;
+        let mut depth = 0;
+        while let Some(subcommand) = words.get(depth).and_then(|word| command.subcommand(word)) {
+            command = subcommand;
+            depth += 1;
+        }
+        (command, depth)
+    }
+
+    /// Fills in defaults for unset options; options without a long name are never filled in.
+    pub fn with_defaults(&self, words: Vec<String>, defaults: &[(String, String)]) -> Vec<String> {
+        let (command, _) = self.resolve(&words);
+        let mut completed_words = words.clone();
+
+        for (name, value) in defaults {
+            let option = match command.options.iter().find(|option| &option.name == name) {
+                Some(option) => option,
+                None => continue
+            };
+            let long = match &option.long {
+                Some(long) => format!("--{}", long),
+                None => continue
+            };
+            let short = option.short.as_ref().map(|short| format!("-{}", short));
+            let is_set = words.iter().any(|word| word == &long
+                || word.starts_with(&format!("{}=", long))
+                || short.as_ref().map_or(false, |short| word.starts_with(short.as_str())));
+            if !is_set {
+                completed_words.push(long);
+                completed_words.push(value.clone());
+            }
+        }
+
+        completed_words
+    }
+}
+
+fn entries_of(yaml: &Yaml) -> Vec<(String, &Yaml)> {
+    yaml.as_vec().map(|entries| entries.iter()
+            .filter_map(|entry| entry.as_hash())
+            .flat_map(|entry| entry.iter())
+            .filter_map(|(name, value)| name.as_str().map(|name| (name.to_string(), value)))
+            .collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use yaml_rust::YamlLoader;
+    use crate::console::definition::CommandDefinition;
+
+    const DEFINITION: &str = "
+name: client
+subcommands:
+  - batch:
+      subcommands:
+        - create:
+            args:
+              - agent_id:
+                  short: i
+                  long: id
+              - message_type:
+                  short: t
+                  long: type
+";
+
+    fn words(line: &str) -> Vec<String> {
+        line.split_whitespace().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn it_resolves_nested_subcommands_of_the_definition() {
+        let yaml = YamlLoader::load_from_str(DEFINITION).unwrap();
+        let definition = CommandDefinition::from_yaml("client", &yaml[0]);
+
+        let (command, depth) = definition.resolve(&words("batch create --id robot1"));
+
+        assert_eq!(command.name, "create");
+        assert_eq!(depth, 2);
+        assert_eq!(command.options.len(), 2);
+    }
+
+    #[test]
+    fn it_appends_defaults_only_for_options_that_are_not_set() {
+        let yaml = YamlLoader::load_from_str(DEFINITION).unwrap();
+        let definition = CommandDefinition::from_yaml("client", &yaml[0]);
+        let defaults = vec![("agent_id".to_string(), "robot1".to_string()),
+                            ("message_type".to_string(), "SyncTalk".to_string()),
+                            ("filter".to_string(), "agent_id=robot1".to_string())];
+
+        let completed_words = definition.with_defaults(words("batch create -t SyncReady"), &defaults);
+
+        assert_eq!(completed_words, words("batch create -t SyncReady --id robot1"));
+    }
+}
@@ -0,0 +1,218 @@
+pub mod completion;
+pub mod definition;
+
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use rustyline::Editor;
+use rustyline::error::ReadlineError;
+use yaml_rust::Yaml;
+use crate::command::{self, SawtoothCommand};
+use crate::console::completion::CommandCompletion;
+use crate::console::definition::CommandDefinition;
+
+const PROMPT: &str = "alica> ";
+const HISTORY_FILE_NAME: &str = ".alica_messages_history";
+const CONSOLE_COMMANDS: [&str; 6] = ["set", "unset", "help", "exit", "quit", "interactive"];
+
+/// Runs commands line by line with the global arguments the console was started with.
+pub struct Console<'y> {
+    cli_definition: &'y Yaml,
+    definition: CommandDefinition,
+    defaults: Vec<(String, String)>,
+    history_file: Option<PathBuf>
+}
+
+impl<'y> Console<'y> {
+    pub fn new(cli_definition: &'y Yaml) -> Self {
+        let name = cli_definition["name"].as_str().unwrap_or_default();
+        Console {
+            cli_definition,
+            definition: CommandDefinition::from_yaml(name, cli_definition),
+            defaults: Vec::new(),
+            history_file: dirs::home_dir().map(|home_dir| home_dir.join(HISTORY_FILE_NAME))
+        }
+    }
+
+    pub fn run<'a, F>(&mut self, create_command: F)
+        where F: Fn(&clap::ArgMatches) -> Result<Box<dyn SawtoothCommand + 'a>, command::Error> {
+        let mut editor = Editor::<CommandCompletion>::new();
+        editor.set_helper(Some(CommandCompletion::new(self.definition.clone(), &CONSOLE_COMMANDS)));
+        if let Some(history_file) = &self.history_file {
+            let _ = editor.load_history(history_file);
+        }
+
+        println!("Enter a command without the program name, \"help\" to list the commands or \"exit\" to leave");
+        loop {
+            match editor.readline(PROMPT) {
+                Ok(line) => {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    editor.add_history_entry(line.as_str());
+                    if !self.execute_line(&line, &create_command) {
+                        break;
+                    }
+                },
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => break,
+                Err(error) => {
+                    eprintln!("Could not read input: {}", error);
+                    break;
+                }
+            }
+        }
+
+        if let Some(history_file) = &self.history_file {
+            if editor.save_history(history_file).is_err() {
+                eprintln!("Could not save the history to {}", history_file.display());
+            }
+        }
+    }
+
+    /// Runs a single line and returns whether the console should read the next one.
+    fn execute_line<'a, F>(&mut self, line: &str, create_command: &F) -> bool
+        where F: Fn(&clap::ArgMatches) -> Result<Box<dyn SawtoothCommand + 'a>, command::Error> {
+        let words = match shlex::split(line) {
+            Some(words) => words,
+            None => {
+                eprintln!("Unbalanced quotes in \"{}\"", line);
+                return true;
+            }
+        };
+
+        match words.first().map(|word| word.as_str()) {
+            None => {},
+            Some("exit") | Some("quit") => return false,
+            Some("help") => self.print_help(),
+            Some("set") => self.set_default(&words[1..]),
+            Some("unset") => self.unset_default(&words[1..]),
+            Some("interactive") => eprintln!("The interactive console is already running"),
+            Some(_) => {
+                let words = self.definition.with_defaults(words, &self.defaults);
+                match clap::App::from(self.cli_definition)
+                    .setting(clap::AppSettings::NoBinaryName)
+                    .get_matches_from_safe(words) {
+                    Ok(command_args) => match self.session_option_in(&command_args) {
+                        Some(option) => eprintln!("{} applies to the whole session, restart the console to change it", option),
+                        None => run_command(&command_args, create_command)
+                    },
+                    Err(error) => eprintln!("{}", error.message)
+                }
+            }
+        }
+        true
+    }
+
+    fn session_option_in(&self, command_args: &clap::ArgMatches) -> Option<String> {
+        self.definition.options.iter()
+            .find(|option| command_args.occurrences_of(&option.name) > 0)
+            .map(|option| match &option.long {
+                Some(long) => format!("--{}", long),
+                None => option.name.clone()
+            })
+    }
+
+    fn set_default(&mut self, arguments: &[String]) {
+        match arguments {
+            [] => for (name, value) in &self.defaults {
+                println!("{} = \"{}\"", name, value);
+            },
+            [name, value] => {
+                self.defaults.retain(|(default_name, _)| default_name != name);
+                self.defaults.push((name.clone(), value.clone()));
+            },
+            _ => eprintln!("Usage: set [NAME VALUE], e.g. set agent_id robot1")
+        }
+    }
+
+    fn unset_default(&mut self, arguments: &[String]) {
+        match arguments {
+            [name] => self.defaults.retain(|(default_name, _)| default_name != name),
+            _ => eprintln!("Usage: unset NAME")
+        }
+    }
+
+    fn print_help(&self) {
+        let mut app = clap::App::from(self.cli_definition).setting(clap::AppSettings::NoBinaryName);
+        let _ = app.print_help();
+        println!();
+        println!();
+        println!("CONSOLE COMMANDS:");
+        println!("    set [NAME VALUE]    Lists the session defaults or uses VALUE for every option NAME a command does not set");
+        println!("    unset NAME          Removes a session default");
+        println!("    help                Prints this help, append --help to a command for its options");
+        println!("    exit, quit          Leaves the console");
+    }
+}
+
+/// Runs a command without ending the session when it fails or panics.
+fn run_command<'a, F>(command_args: &clap::ArgMatches, create_command: &F)
+    where F: Fn(&clap::ArgMatches) -> Result<Box<dyn SawtoothCommand + 'a>, command::Error> {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| create_command(command_args).and_then(|command| command.execute())));
+    match result {
+        Ok(Ok(())) => {},
+        Ok(Err(command::Error::ExecutionError(message))) => eprintln!("Command execution failed: {}", message),
+        Err(_) => eprintln!("Command could not be run")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+    use yaml_rust::YamlLoader;
+    use crate::command::{self, ExecutionResult, SawtoothCommand};
+    use crate::console::Console;
+
+    const DEFINITION: &str = "
+name: client
+args:
+  - idempotent:
+      long: idempotent
+      takes_value: false
+subcommands:
+  - state:
+      subcommands:
+        - list:
+            args:
+              - output:
+                  long: output
+                  takes_value: true
+";
+
+    struct NoCommand;
+
+    impl SawtoothCommand for NoCommand {
+        fn execute(&self) -> ExecutionResult {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn it_skips_lines_without_words() {
+        let yaml = YamlLoader::load_from_str(DEFINITION).unwrap();
+        let mut console = Console::new(&yaml[0]);
+        let created_commands = Cell::new(0);
+        let create_command = |_: &clap::ArgMatches| -> Result<Box<dyn SawtoothCommand>, command::Error> {
+            created_commands.set(created_commands.get() + 1);
+            Ok(Box::new(NoCommand))
+        };
+
+        assert!(console.execute_line("# note", &create_command));
+        assert_eq!(created_commands.get(), 0);
+    }
+
+    #[test]
+    fn it_rejects_global_options_on_command_lines() {
+        let yaml = YamlLoader::load_from_str(DEFINITION).unwrap();
+        let mut console = Console::new(&yaml[0]);
+        let created_commands = Cell::new(0);
+        let create_command = |_: &clap::ArgMatches| -> Result<Box<dyn SawtoothCommand>, command::Error> {
+            created_commands.set(created_commands.get() + 1);
+            Ok(Box::new(NoCommand))
+        };
+
+        assert!(console.execute_line("--idempotent state list", &create_command));
+        assert!(console.execute_line("state list --output json", &create_command));
+        assert_eq!(created_commands.get(), 1);
+    }
+}
@@ -0,0 +1,178 @@
+pub mod view;
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+use sawtooth_alica_payload::payloads::TransactionPayload;
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+const FEED_LENGTH: usize = 200;
+
+/// What the dashboard knows about the messages of a single agent.
+pub struct AgentActivity {
+    /// Latest message of every message type by the timestamp of sending.
+    pub latest_messages: BTreeMap<String, TransactionPayload>,
+    /// None if all messages of the agent were committed before the dashboard started.
+    pub last_seen: Option<Instant>,
+    arrivals: VecDeque<Instant>
+}
+
+impl AgentActivity {
+    fn new() -> Self {
+        AgentActivity {
+            latest_messages: BTreeMap::new(),
+            last_seen: None,
+            arrivals: VecDeque::new()
+        }
+    }
+
+    fn record(&mut self, payload: TransactionPayload, observed_at: Option<Instant>) {
+        if let Some(observed_at) = observed_at {
+            self.last_seen = Some(observed_at);
+            self.arrivals.push_back(observed_at);
+        }
+
+        let is_newer = self.latest_messages.get(&payload.message_type)
+            .map_or(true, |latest| latest.timestamp <= payload.timestamp);
+        if is_newer {
+            self.latest_messages.insert(payload.message_type.clone(), payload);
+        }
+    }
+
+    fn forget_arrivals_before(&mut self, instant: Instant) {
+        while self.arrivals.front().map_or(false, |arrival| *arrival < instant) {
+            self.arrivals.pop_front();
+        }
+    }
+
+    /// Number of new messages of the agent within the last minute.
+    pub fn messages_per_minute(&self) -> usize {
+        self.arrivals.len()
+    }
+
+    /// Time since the last new message, measured from the start of the dashboard if there was none.
+    pub fn silence(&self, started_at: Instant, now: Instant) -> Duration {
+        now.saturating_duration_since(self.last_seen.unwrap_or(started_at))
+    }
+}
+
+/// State entry that was committed while the dashboard was running.
+pub struct FeedEntry {
+    pub address: String,
+    pub agent_id: String,
+    pub message_type: String,
+    pub timestamp: u64
+}
+
+/// Activity of all agents, built from repeated snapshots of the state entries.
+pub struct MissionState {
+    started_at: Instant,
+    agents: BTreeMap<String, AgentActivity>,
+    known_addresses: HashSet<String>,
+    feed: VecDeque<FeedEntry>,
+    initialized: bool
+}
+
+impl MissionState {
+    pub fn new(started_at: Instant) -> Self {
+        MissionState {
+            started_at,
+            agents: BTreeMap::new(),
+            known_addresses: HashSet::new(),
+            feed: VecDeque::new(),
+            initialized: false
+        }
+    }
+
+    /// Entries of the first snapshot show up as latest messages but neither in the feed nor in the rates.
+    pub fn update(&mut self, entries: Vec<(String, TransactionPayload)>, now: Instant) {
+        let observed_at = if self.initialized { Some(now) } else { None };
+
+        let mut new_entries: Vec<(String, TransactionPayload)> = entries.into_iter()
+            .filter(|(address, _)| !self.known_addresses.contains(address))
+            .collect();
+        new_entries.sort_by_key(|(_, payload)| payload.timestamp);
+
+        for (address, payload) in new_entries {
+            if self.initialized {
+                self.feed.push_front(FeedEntry {
+                    address: address.clone(),
+                    agent_id: payload.agent_id.clone(),
+                    message_type: payload.message_type.clone(),
+                    timestamp: payload.timestamp
+                });
+            }
+            self.known_addresses.insert(address);
+            self.agents.entry(payload.agent_id.clone())
+                .or_insert_with(AgentActivity::new)
+                .record(payload, observed_at);
+        }
+
+        self.feed.truncate(FEED_LENGTH);
+        if let Some(window_start) = now.checked_sub(RATE_WINDOW) {
+            for activity in self.agents.values_mut() {
+                activity.forget_arrivals_before(window_start);
+            }
+        }
+        self.initialized = true;
+    }
+
+    pub fn started_at(&self) -> Instant {
+        self.started_at
+    }
+
+    pub fn agents(&self) -> &BTreeMap<String, AgentActivity> {
+        &self.agents
+    }
+
+    /// Newly committed entries, the most recent first.
+    pub fn feed(&self) -> &VecDeque<FeedEntry> {
+        &self.feed
+    }
+
+    /// Agents without a new message for longer than the threshold.
+    pub fn silent_agents(&self, threshold: Duration, now: Instant) -> Vec<&str> {
+        self.agents.iter()
+            .filter(|(_, activity)| activity.silence(self.started_at, now) > threshold)
+            .map(|(agent_id, _)| agent_id.as_str())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+    use sawtooth_alica_payload::payloads::TransactionPayload;
+    use crate::dashboard::MissionState;
+
+    fn entry(address: &str, agent_id: &str, message_type: &str, timestamp: u64) -> (String, TransactionPayload) {
+        (address.to_string(), TransactionPayload::new(agent_id, message_type, "message".as_bytes(), timestamp))
+    }
+
+    #[test]
+    fn it_feeds_only_entries_committed_after_the_first_snapshot() {
+        let started_at = Instant::now();
+        let mut state = MissionState::new(started_at);
+
+        state.update(vec![entry("a1", "robot1", "AlicaEngineInfo", 1)], started_at);
+        state.update(vec![entry("a1", "robot1", "AlicaEngineInfo", 1), entry("a2", "robot1", "AlicaEngineInfo", 2)],
+                     started_at + Duration::from_secs(1));
+
+        assert_eq!(state.feed().len(), 1);
+        assert_eq!(state.feed()[0].address, "a2");
+        let activity = &state.agents()["robot1"];
+        assert_eq!(activity.messages_per_minute(), 1);
+        assert_eq!(activity.latest_messages["AlicaEngineInfo"].timestamp, 2);
+    }
+
+    #[test]
+    fn it_reports_agents_without_new_messages_as_silent() {
+        let started_at = Instant::now();
+        let mut state = MissionState::new(started_at);
+        state.update(vec![entry("a1", "robot1", "SyncTalk", 1), entry("a2", "robot2", "SyncTalk", 1)], started_at);
+
+        let now = started_at + Duration::from_secs(30);
+        state.update(vec![entry("a3", "robot2", "SyncReady", 2)], now);
+
+        assert_eq!(state.silent_agents(Duration::from_secs(10), now), vec!["robot1"]);
+    }
+}
@@ -0,0 +1,103 @@
+use std::time::{Duration, Instant};
+use tui::backend::Backend;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, List, ListItem, Paragraph, Wrap};
+use tui::Frame;
+use crate::dashboard::{AgentActivity, MissionState};
+use crate::output::MessageBody;
+
+const MAX_AGENT_PANES: usize = 6;
+const PREVIEW_LENGTH: usize = 48;
+
+/// How the dashboard is drawn, independent of the data it shows.
+pub struct ViewSettings {
+    pub silence_threshold: Duration,
+    pub status_line: String
+}
+
+pub fn draw<B: Backend>(frame: &mut Frame<B>, state: &MissionState, settings: &ViewSettings, now: Instant) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Percentage(60), Constraint::Min(5)].as_ref())
+        .split(frame.size());
+
+    let silent_agents = state.silent_agents(settings.silence_threshold, now);
+    let summary = format!("{} agents, {} silent for more than {}s | {} | q to quit", state.agents().len(),
+                          silent_agents.len(), settings.silence_threshold.as_secs(), settings.status_line);
+    frame.render_widget(Paragraph::new(summary), rows[0]);
+
+    draw_agents(frame, rows[1], state, settings, now);
+    draw_feed(frame, rows[2], state);
+}
+
+fn draw_agents<B: Backend>(frame: &mut Frame<B>, area: Rect, state: &MissionState, settings: &ViewSettings, now: Instant) {
+    // Silent agents come first, so they stay visible when there are more agents than panes.
+    let mut agents: Vec<(&String, &AgentActivity)> = state.agents().iter().collect();
+    agents.sort_by_key(|(_, activity)| std::cmp::Reverse(activity.silence(state.started_at(), now)));
+    agents.truncate(MAX_AGENT_PANES);
+    if agents.is_empty() {
+        let block = Block::default().borders(Borders::ALL).title("Agents");
+        frame.render_widget(Paragraph::new("No messages recorded yet").block(block), area);
+        return;
+    }
+
+    let constraints: Vec<Constraint> = agents.iter()
+        .map(|_| Constraint::Ratio(1, agents.len() as u32))
+        .collect();
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(constraints)
+        .split(area);
+
+    for ((agent_id, activity), pane) in agents.into_iter().zip(panes) {
+        let silence = activity.silence(state.started_at(), now);
+        let border_style = if silence > settings.silence_threshold {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Green)
+        };
+        let last_seen = match activity.last_seen {
+            Some(_) => format!("{}s ago", silence.as_secs()),
+            None => format!("before start ({}s)", silence.as_secs())
+        };
+
+        let mut lines = vec![
+            Spans::from(format!("Rate: {}/min", activity.messages_per_minute())),
+            Spans::from(format!("Last message: {}", last_seen)),
+            Spans::from("")
+        ];
+        for (message_type, payload) in &activity.latest_messages {
+            lines.push(Spans::from(Span::styled(message_type.clone(), Style::default().add_modifier(Modifier::BOLD))));
+            lines.push(Spans::from(format!("  {} {}", payload.timestamp, preview_of(&MessageBody::of(payload)))));
+        }
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(Span::styled(agent_id.clone(), border_style));
+        frame.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: true }), pane);
+    }
+}
+
+fn draw_feed<B: Backend>(frame: &mut Frame<B>, area: Rect, state: &MissionState) {
+    let items: Vec<ListItem> = state.feed().iter()
+        .take(area.height as usize)
+        .map(|entry| ListItem::new(format!("{} {} {} {}", entry.timestamp, entry.agent_id, entry.message_type, entry.address)))
+        .collect();
+    let block = Block::default().borders(Borders::ALL).title("Newly committed messages");
+    frame.render_widget(List::new(items).block(block), area);
+}
+
+fn preview_of(message_body: &MessageBody) -> String {
+    let preview = match message_body {
+        MessageBody::Decoded(decoded_message) => decoded_message.to_string(),
+        MessageBody::Text(text) => text.clone(),
+        MessageBody::Binary(hex) => hex.clone()
+    };
+    match preview.char_indices().nth(PREVIEW_LENGTH) {
+        Some((end, _)) => format!("{}...", &preview[..end]),
+        None => preview
+    }
+}
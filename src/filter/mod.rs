@@ -6,6 +6,14 @@ pub trait TransactionPayloadFilter {
     fn filter(&self, payloads: &mut Vec<TransactionPayload>);
 }
 
+pub fn apply_all(filters: &[Box<dyn TransactionPayloadFilter>], payload: TransactionPayload) -> Option<TransactionPayload> {
+    let mut payloads = vec![payload];
+    for filter in filters {
+        filter.filter(&mut payloads);
+    }
+    payloads.pop()
+}
+
 pub struct AgentIdFilter {
     required_id: String
 }
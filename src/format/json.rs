@@ -0,0 +1,100 @@
+use serde_json::{json, Map, Value};
+use sawtooth_alica_payload::payloads;
+use sawtooth_alica_payload::payloads::TransactionPayload;
+
+const FIELDS: [&str; 4] = ["agent_id", "message_type", "message", "timestamp"];
+
+/// Payloads as JSON objects with the message in base64, objects with other fields are rejected.
+#[derive(Default)]
+pub struct Format {}
+
+impl payloads::Format for Format {
+    fn serialize(&self, payload: &TransactionPayload) -> Result<Vec<u8>, payloads::Error> {
+        let json_payload = json!({
+            "agent_id": payload.agent_id,
+            "message_type": payload.message_type,
+            "message": data_encoding::BASE64.encode(&payload.message_bytes),
+            "timestamp": payload.timestamp
+        });
+
+        serde_json::to_vec(&json_payload)
+            .map_err(|error| payloads::Error::InvalidPayload(format!("Could not encode payload as JSON: {}", error)))
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<TransactionPayload, payloads::Error> {
+        let json_payload = serde_json::from_slice::<Value>(bytes)
+            .map_err(|error| payloads::Error::InvalidPayload(format!("Payload is not valid JSON: {}", error)))?;
+        let fields = match json_payload {
+            Value::Object(fields) => fields,
+            _ => return Err(payloads::Error::InvalidPayload("Payload is not a JSON object".to_string()))
+        };
+
+        if let Some(unknown_field) = fields.keys().find(|field| !FIELDS.contains(&field.as_str())) {
+            return Err(payloads::Error::InvalidPayload(format!("Unknown field \"{}\" in payload", unknown_field)));
+        }
+
+        let agent_id = string_field(&fields, "agent_id")?;
+        let message_type = string_field(&fields, "message_type")?;
+        let message = data_encoding::BASE64.decode(string_field(&fields, "message")?.as_bytes())
+            .map_err(|_| payloads::Error::InvalidPayload("Field \"message\" is not valid base64".to_string()))?;
+        let timestamp = fields.get("timestamp")
+            .ok_or(payloads::Error::InvalidPayload("Field \"timestamp\" is missing".to_string()))?
+            .as_u64()
+            .ok_or(payloads::Error::InvalidTimestamp)?;
+
+        Ok(TransactionPayload::new(agent_id, message_type, &message, timestamp))
+    }
+}
+
+fn string_field<'a>(fields: &'a Map<String, Value>, name: &str) -> Result<&'a str, payloads::Error> {
+    fields.get(name)
+        .ok_or(payloads::Error::InvalidPayload(format!("Field \"{}\" is missing", name)))?
+        .as_str()
+        .ok_or(payloads::Error::InvalidPayload(format!("Field \"{}\" is not a string", name)))
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+    use sawtooth_alica_payload::payloads::{Format, TransactionPayload};
+    use crate::format::json;
+
+    proptest! {
+        #[test]
+        fn it_restores_every_serialized_payload(agent_id in ".*", message_type in ".*",
+                                                message in proptest::collection::vec(any::<u8>(), 0..256),
+                                                timestamp in any::<u64>()) {
+            let format = json::Format::default();
+            let payload = TransactionPayload::new(&agent_id, &message_type, &message, timestamp);
+
+            let bytes = format.serialize(&payload).ok().expect("Serialization failed");
+            let restored_payload = format.deserialize(&bytes).ok().expect("Deserialization failed");
+
+            prop_assert_eq!(restored_payload.agent_id, agent_id);
+            prop_assert_eq!(restored_payload.message_type, message_type);
+            prop_assert_eq!(restored_payload.message_bytes, message);
+            prop_assert_eq!(restored_payload.timestamp, timestamp);
+        }
+    }
+
+    #[test]
+    fn it_rejects_payloads_with_unknown_fields() {
+        let bytes = r#"{"agent_id": "a", "message_type": "t", "message": "", "timestamp": 1, "extra": 0}"#;
+
+        assert!(json::Format::default().deserialize(bytes.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn it_rejects_payloads_with_missing_fields() {
+        let bytes = r#"{"agent_id": "a", "message_type": "t", "timestamp": 1}"#;
+
+        assert!(json::Format::default().deserialize(bytes.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn it_rejects_negative_timestamps() {
+        let bytes = r#"{"agent_id": "a", "message_type": "t", "message": "", "timestamp": -1}"#;
+
+        assert!(json::Format::default().deserialize(bytes.as_bytes()).is_err());
+    }
+}
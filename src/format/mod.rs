@@ -0,0 +1,78 @@
+pub mod json;
+
+use sawtooth_alica_payload::payloads;
+use sawtooth_alica_payload::payloads::TransactionPayload;
+
+pub struct FormatRegistry {
+    formats: Vec<(String, Box<dyn payloads::Format>)>
+}
+
+impl FormatRegistry {
+    pub fn new() -> Self {
+        FormatRegistry {
+            formats: Vec::new()
+        }
+    }
+
+    pub fn register(&mut self, name: &str, format: Box<dyn payloads::Format>) {
+        self.formats.retain(|(registered_name, _)| registered_name != name);
+        self.formats.push((name.to_string(), format));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn payloads::Format> {
+        self.formats.iter()
+            .find(|(registered_name, _)| registered_name == name)
+            .map(|(_, format)| format.as_ref())
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.formats.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    pub fn detect(&self, bytes: &[u8]) -> Option<(&str, TransactionPayload)> {
+        self.formats.iter()
+            .filter_map(|(name, format)| format.deserialize(bytes).ok().map(|payload| (name.as_str(), payload)))
+            .next()
+    }
+}
+
+pub fn reserved_characters_of(format_name: &str) -> &'static [char] {
+    match format_name {
+        "pipe-separated" => &['|'],
+        _ => &[]
+    }
+}
+
+impl Default for FormatRegistry {
+    fn default() -> Self {
+        let mut registry = FormatRegistry::new();
+        registry.register("json", Box::from(json::Format::default()));
+        registry.register("pipe-separated", Box::from(payloads::pipe_separated::Format::default()));
+        registry
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sawtooth_alica_payload::payloads::{self, Format, TransactionPayload};
+    use crate::format::FormatRegistry;
+
+    #[test]
+    fn it_reports_the_format_that_decoded_a_payload() {
+        let registry = FormatRegistry::default();
+        let payload = TransactionPayload::new("agent1", "type", "message".as_bytes(), 69182798179);
+        let bytes = payloads::pipe_separated::Format::default().serialize(&payload).ok().expect("Serialization failed");
+
+        let (name, detected_payload) = registry.detect(&bytes).expect("No format detected");
+
+        assert_eq!(name, "pipe-separated");
+        assert_eq!(detected_payload.agent_id, "agent1");
+    }
+
+    #[test]
+    fn it_detects_nothing_without_registered_formats() {
+        let registry = FormatRegistry::new();
+
+        assert!(registry.detect("agent1|type|message|69182798179".as_bytes()).is_none());
+    }
+}
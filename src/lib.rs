@@ -1,13 +1,30 @@
 use sawtooth_sdk::signing;
 use std::path::{Path, PathBuf};
-use std::{fs, env};
-use sawtooth_alica_payload::payloads;
-use crate::sawtooth::ComponentFactory;
+use std::{fs, env, io};
+use std::io::Read;
+use std::time::Duration;
+use sawtooth_alica_payload::{payloads, TransactionFamily};
+use crate::sawtooth::{ComponentFactory, TransactionOptions};
+use crate::sawtooth::transport::memory::SimulatedLedger;
+use crate::sawtooth::transport::mirror::Mirror;
+use crate::sawtooth::factory::{GeneralPurposeComponentFactory, NonceStrategy};
 use crate::filter::{TransactionPayloadFilter, AgentIdFilter, MessageTypeFilter};
+use crate::format::FormatRegistry;
+use crate::validation::{PayloadValidator, ReservedCharacterValidator, RoundTripValidator, MessageTypeValidator};
+use crate::alica::MessageTypeRegistry;
+use crate::output::OutputFormat;
+use crate::command::replay::Routes;
+use crate::command::record::RecordCommand;
 
 pub mod sawtooth;
 pub mod command;
 pub mod filter;
+pub mod format;
+pub mod validation;
+pub mod alica;
+pub mod output;
+pub mod console;
+pub mod dashboard;
 
 pub fn create_alica_message(args: &clap::ArgMatches) -> payloads::TransactionPayload {
     payloads::TransactionPayload::new(
@@ -19,6 +36,29 @@ pub fn create_alica_message(args: &clap::ArgMatches) -> payloads::TransactionPay
     )
 }
 
+pub fn create_transaction_options(args: &clap::ArgMatches) -> TransactionOptions {
+    let values_of = |name| args.values_of(name)
+        .map(|values| values.map(|value| value.to_string()).collect())
+        .unwrap_or_default();
+
+    TransactionOptions {
+        inputs: values_of("input_address"),
+        outputs: values_of("output_address"),
+        dependencies: values_of("depends_on"),
+        idempotency_key: args.value_of("idempotency_key").map(|key| key.to_string())
+    }
+}
+
+pub fn read_input(path: &str) -> Vec<u8> {
+    let mut contents = Vec::new();
+    if path == "-" {
+        io::stdin().read_to_end(&mut contents).expect("Could not read from stdin");
+    } else {
+        contents = fs::read(path).unwrap_or_else(|error| panic!("Could not read {}: {}", path, error));
+    }
+    contents
+}
+
 pub fn get_or_create_keyfile(args: &clap::ArgMatches) -> Option<Box<Path>> {
     args.value_of("key file").map(|path| PathBuf::from(path).into_boxed_path())
 }
@@ -49,33 +89,218 @@ pub fn create_signer<'a>(path: &Box<Path>) -> signing::Signer<'a> {
     signing::Signer::new_boxed(context, private_key)
 }
 
-pub fn create_sawtooth_client<'a>(args: &clap::ArgMatches, factory: &'a dyn ComponentFactory) -> sawtooth::Client<'a> {
-    let validator_url = args.value_of("connect").expect("Validator address missing");
-    sawtooth::Client::new(validator_url, factory)
+pub fn create_existing_signer<'a>(path: &Box<Path>) -> signing::Signer<'a> {
+    if !path.exists() {
+        panic!("There is no key file at {}", path.display());
+    }
+    let private_key = read_existing_private_key(path);
+    let context = create_context_for_private_key(&private_key);
+
+    signing::Signer::new_boxed(context, private_key)
+}
+
+pub fn select_nonce_strategy(args: &clap::ArgMatches) -> NonceStrategy {
+    if args.is_present("idempotent") {
+        NonceStrategy::Derived
+    } else {
+        NonceStrategy::Random
+    }
+}
+
+pub fn configure_batcher<'a>(args: &clap::ArgMatches, factory: GeneralPurposeComponentFactory<'a>)
+                             -> GeneralPurposeComponentFactory<'a> {
+    match (args.value_of("batcher_key_file"), args.value_of("batcher_public_key")) {
+        (Some(path), _) => factory.with_batcher(create_existing_signer(&PathBuf::from(path).into_boxed_path())),
+        (None, Some(public_key)) => factory.with_remote_batcher(public_key),
+        (None, None) => factory
+    }
+}
+
+pub fn create_sawtooth_client<'a>(args: &clap::ArgMatches, factory: &'a dyn ComponentFactory,
+                                  transaction_family: &'a TransactionFamily, payload_format: &'a dyn payloads::Format)
+                                  -> sawtooth::Client<'a> {
+    if args.is_present("offline") {
+        let mirror_path = PathBuf::from(args.value_of("mirror").expect("Mirror directory missing"));
+        if !mirror_path.exists() {
+            panic!("There is no mirror at {}, run the sync command first", mirror_path.display());
+        }
+        let mirror = Mirror::open(&mirror_path).expect("Could not load mirror");
+        return sawtooth::Client::with_transport(Box::from(mirror), factory);
+    }
+
+    match args.value_of("backend").unwrap_or("validator") {
+        "memory" => {
+            let ledger = SimulatedLedger::new(transaction_family, payload_format);
+            sawtooth::Client::with_transport(Box::from(ledger), factory)
+        },
+        "file" => {
+            let ledger_file = PathBuf::from(args.value_of("ledger_file").expect("Ledger file missing"));
+            let ledger = SimulatedLedger::with_file(transaction_family, payload_format, &ledger_file)
+                .expect("Could not load ledger file");
+            sawtooth::Client::with_transport(Box::from(ledger), factory)
+        },
+        _ => {
+            let validator_url = args.value_of("connect").expect("Validator address missing");
+            sawtooth::Client::new(validator_url, factory).expect("Could not connect to validator")
+        }
+    }
+}
+
+pub fn create_transaction_family(args: &clap::ArgMatches) -> TransactionFamily {
+    let family_name = args.value_of("family_name").unwrap_or("alica_messages");
+    TransactionFamily::new(family_name, &family_versions_of(args))
+}
+
+pub fn select_family_version(args: &clap::ArgMatches, transaction_family: &TransactionFamily) -> String {
+    match args.value_of("submit_version") {
+        Some(version) if family_versions_of(args).iter().any(|configured| configured == version) => version.to_string(),
+        Some(version) => panic!("Version \"{}\" is not one of the configured family versions {}",
+                                version, family_versions_of(args).join(", ")),
+        None => transaction_family.latest_version().clone()
+    }
 }
 
-pub fn create_filters(args: &clap::ArgMatches) -> Vec<Box<dyn TransactionPayloadFilter>> {
+pub fn create_version_formats<'a>(args: &clap::ArgMatches, format_registry: &'a FormatRegistry,
+                                  payload_format: &'a dyn payloads::Format) -> Vec<(String, &'a dyn payloads::Format)> {
+    let version_formats: Vec<(String, String)> = match args.values_of("version_format") {
+        Some(values) => values.map(|value| split_pair(value, "VERSION=FORMAT")).collect(),
+        None => Vec::new()
+    };
+
+    let family_versions = family_versions_of(args);
+    if let Some((version, _)) = version_formats.iter().find(|(version, _)| !family_versions.contains(version)) {
+        panic!("Version \"{}\" is not one of the configured family versions {}", version, family_versions.join(", "))
+    }
+
+    family_versions.into_iter()
+        .map(|version| {
+            let format = match version_formats.iter().find(|(mapped_version, _)| mapped_version == &version) {
+                Some((_, format_name)) => format_registry.get(format_name)
+                    .unwrap_or_else(|| panic!("Unknown payload format \"{}\", available formats are {}",
+                                              format_name, format_registry.names().join(", "))),
+                None => payload_format
+            };
+            (version, format)
+        })
+        .collect()
+}
+
+fn family_versions_of(args: &clap::ArgMatches) -> Vec<String> {
+    match args.values_of("family_version") {
+        Some(values) => values.map(|version| version.to_string()).collect(),
+        None => vec!["0.1.0".to_string()]
+    }
+}
+
+pub fn select_payload_format<'a>(args: &clap::ArgMatches, format_registry: &'a FormatRegistry) -> &'a dyn payloads::Format {
+    let format_name = args.value_of("payload_format").unwrap_or("pipe-separated");
+    format_registry.get(format_name).unwrap_or_else(|| panic!("Unknown payload format \"{}\", available formats are {}",
+                                                                format_name, format_registry.names().join(", ")))
+}
+
+pub fn create_validators<'a>(global_args: &clap::ArgMatches, args: &clap::ArgMatches, payload_format: &'a dyn payloads::Format,
+                             message_types: &'a MessageTypeRegistry) -> Vec<Box<dyn PayloadValidator + 'a>> {
+    let format_name = global_args.value_of("payload_format").unwrap_or("pipe-separated");
+    let mut validators: Vec<Box<dyn PayloadValidator + 'a>> = Vec::new();
+
+    validators.push(Box::from(MessageTypeValidator::new(message_types, args.is_present("allow_unknown_type"))));
+
+    let reserved_characters = format::reserved_characters_of(format_name);
+    if !reserved_characters.is_empty() {
+        validators.push(Box::from(ReservedCharacterValidator::new(format_name, reserved_characters)));
+    }
+    if args.is_present("strict") {
+        validators.push(Box::from(RoundTripValidator::new(payload_format)));
+    }
+
+    validators
+}
+
+pub fn select_output_format(args: &clap::ArgMatches) -> OutputFormat {
+    let output_format = args.value_of("output").unwrap_or("text");
+    OutputFormat::from_name(output_format).unwrap_or_else(|| panic!("Invalid output format \"{}\" supplied", output_format))
+}
+
+pub fn create_filters(args: &clap::ArgMatches) -> Result<Vec<Box<dyn TransactionPayloadFilter>>, command::Error> {
     match args.values_of("filter") {
         Some(values) => values.map(|filter_condition| {
-                let (key, value) = dissect_filter_condition(filter_condition);
+                let (key, value) = dissect_filter_condition(filter_condition)?;
                 determine_filter(&key, &value)
             }).collect(),
-        None => Vec::new()
+        None => Ok(Vec::new())
     }
 }
 
-fn dissect_filter_condition(condition: &str) -> (String, String) {
-    let mut components = condition.split("=");
-    let key = components.next().expect("No key found, filter needs to be KEY=VALUE");
-    let value = components.next().expect("No value found, filter needs to be KEY=VALUE");
-    (key.to_string(), value.to_string())
+pub fn create_replay_routes(args: &clap::ArgMatches) -> Routes {
+    let mut routes = Routes::new(args.value_of("endpoint"));
+    for route in args.values_of("publish").into_iter().flatten() {
+        let (message_type, endpoint) = split_pair(route, "TYPE=ENDPOINT");
+        routes = routes.with_endpoint(&message_type, &endpoint);
+    }
+    for route in args.values_of("topic").into_iter().flatten() {
+        let (message_type, topic) = split_pair(route, "TYPE=TOPIC");
+        routes = routes.with_topic(&message_type, &topic);
+    }
+    if routes.endpoints().is_empty() {
+        panic!("No endpoint to publish on, use --endpoint or --publish TYPE=ENDPOINT");
+    }
+    routes
 }
 
-fn determine_filter(key: &str, value: &str) -> Box<dyn TransactionPayloadFilter> {
+pub fn configure_recorder<'a>(args: &clap::ArgMatches, command: RecordCommand<'a>) -> RecordCommand<'a> {
+    let parse_count = |name, description| args.value_of(name).expect("Value missing").parse::<usize>().ok()
+        .filter(|count| *count > 0)
+        .unwrap_or_else(|| panic!("{} has to be a positive integer", description));
+    let mut command = command
+        .with_timestamp_unit(select_timestamp_unit(args))
+        .with_batch_size(parse_count("batch_size", "Batch size"))
+        .with_flush_interval(Duration::from_millis(parse_count("flush_interval", "Flush interval") as u64))
+        .with_max_buffered(parse_count("max_buffered", "Maximum of buffered messages"));
+    for route in args.values_of("topic").into_iter().flatten() {
+        let (message_type, topic) = split_pair(route, "TYPE=TOPIC");
+        command = command.with_topic(&message_type, &topic);
+    }
+    if let Some(agent_id) = args.value_of("agent_id") {
+        command = command.with_agent_id(agent_id);
+    }
+    match args.value_of("duration") {
+        Some(duration) => command.with_duration(Duration::from_secs(duration.parse::<u64>()
+            .expect("Duration is not an integer"))),
+        None => command
+    }
+}
+
+pub fn select_timestamp_unit(args: &clap::ArgMatches) -> Duration {
+    match args.value_of("timestamp_unit").unwrap_or("ms") {
+        "s" => Duration::from_secs(1),
+        "ms" => Duration::from_millis(1),
+        "us" => Duration::from_micros(1),
+        "ns" => Duration::from_nanos(1),
+        unit => panic!("Invalid timestamp unit \"{}\" supplied", unit)
+    }
+}
+
+fn dissect_filter_condition(condition: &str) -> Result<(String, String), command::Error> {
+    try_split_pair(condition, "KEY=VALUE").map_err(|error| command::Error::ExecutionError(error))
+}
+
+fn split_pair(pair: &str, form: &str) -> (String, String) {
+    try_split_pair(pair, form).unwrap_or_else(|error| panic!("{}", error))
+}
+
+fn try_split_pair(pair: &str, form: &str) -> Result<(String, String), String> {
+    let mut components = pair.splitn(2, '=');
+    match (components.next(), components.next()) {
+        (Some(key), Some(value)) => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("\"{}\" is not of the form {}", pair, form))
+    }
+}
+
+fn determine_filter(key: &str, value: &str) -> Result<Box<dyn TransactionPayloadFilter>, command::Error> {
     match key {
-        "agent_id" => Box::from(AgentIdFilter::new(value)),
-        "message_type" => Box::from(MessageTypeFilter::new(value)),
-        invalid_filter => panic!("Invalid filter \"{}\" supplied", invalid_filter)
+        "agent_id" => Ok(Box::from(AgentIdFilter::new(value))),
+        "message_type" => Ok(Box::from(MessageTypeFilter::new(value))),
+        invalid_filter => Err(command::Error::ExecutionError(format!("Invalid filter \"{}\" supplied", invalid_filter)))
     }
 }
 
@@ -103,3 +328,18 @@ fn create_new_private_key() -> Box<dyn signing::PrivateKey> {
         .expect("This happens only if the sawtooth team has decided to rename or remove the secp256k1 algorithm");
     context.new_random_private_key().expect("Could not create new private key")
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dissect_filter_condition;
+
+    #[test]
+    fn it_splits_filter_conditions_at_the_first_equals_sign() {
+        assert_eq!(dissect_filter_condition("agent_id=a=b").ok(), Some(("agent_id".to_string(), "a=b".to_string())));
+    }
+
+    #[test]
+    fn it_rejects_filter_conditions_without_an_equals_sign() {
+        assert!(dissect_filter_condition("agent_id").is_err());
+    }
+}
@@ -1,42 +1,303 @@
-use alica_messages_client::{create_alica_message, get_or_create_keyfile, determine_key_file, create_signer, create_sawtooth_client, create_filters};
-use alica_messages_client::command::{SawtoothCommand, batch};
-use alica_messages_client::command::state;
+use alica_messages_client::{create_alica_message, get_or_create_keyfile, determine_key_file, create_signer, create_sawtooth_client, create_filters, select_payload_format, create_validators, select_output_format,
+                              create_transaction_family, select_family_version, create_version_formats,
+                              create_transaction_options, read_input, configure_batcher,
+                              select_nonce_strategy, create_replay_routes, select_timestamp_unit, configure_recorder};
+use alica_messages_client::command::{self, SawtoothCommand, address, batch};
+use alica_messages_client::command::{dashboard, export, network, receipt, record, replay, state, sync, transaction, verify};
 use alica_messages_client::sawtooth::factory::GeneralPurposeComponentFactory;
-use sawtooth_alica_payload::{TransactionFamily, payloads};
+use alica_messages_client::sawtooth::Client;
+use alica_messages_client::sawtooth::transport::mirror::Mirror;
+use alica_messages_client::format::FormatRegistry;
+use alica_messages_client::alica::MessageTypeRegistry;
+use alica_messages_client::console::Console;
+use alica_messages_client::alica::capnzero::{ZmqPublisher, ZmqSubscriber};
+use sawtooth_alica_payload::{payloads, TransactionFamily};
+use std::path::PathBuf;
+use std::time::Duration;
 
 fn main() {
     let cli_definition = clap::load_yaml!("../cli.yml");
     let app = clap::App::from(cli_definition);
     let args = app.get_matches();
 
+    let transaction_family = create_transaction_family(&args);
+    if let ("address", Some(address_args)) = args.subcommand() {
+        create_address_command(address_args, &transaction_family).execute().expect("Command execution failed");
+        return;
+    }
+    if let Some(command) = create_batch_file_verification(&args) {
+        command.execute().expect("Command execution failed");
+        return;
+    }
+
     let configured_key_file = get_or_create_keyfile(&args);
     let key_file = determine_key_file(configured_key_file);
     let signer = create_signer(&key_file);
 
-    let payload_format = payloads::pipe_separated::Format::default();
-    let transaction_family = TransactionFamily::new("alica_messages", &vec!["0.1.0".to_string()]);
-    let component_factory = GeneralPurposeComponentFactory::new(&transaction_family, &payload_format, signer);
+    let format_registry = FormatRegistry::default();
+    let payload_format = select_payload_format(&args, &format_registry);
+    let message_types = MessageTypeRegistry::default();
+    let component_factory = GeneralPurposeComponentFactory::new(&transaction_family, payload_format, signer)
+        .with_family_version(&select_family_version(&args, &transaction_family));
+    let component_factory = configure_batcher(&args, component_factory)
+        .with_nonce_strategy(select_nonce_strategy(&args));
+
+    let context = Context {
+        transaction_family: &transaction_family,
+        format_registry: &format_registry,
+        payload_format,
+        message_types: &message_types,
+        component_factory: &component_factory
+    };
+
+    if let ("interactive", Some(_)) = args.subcommand() {
+        let client = create_sawtooth_client(&args, &component_factory, &transaction_family, payload_format);
+        Console::new(cli_definition)
+            .run(|command_args| create_command(&args, command_args, &context, &|| client.clone()));
+        return;
+    }
 
-    let client = create_sawtooth_client(&args, &component_factory);
+    let client = || create_sawtooth_client(&args, &component_factory, &transaction_family, payload_format);
+    create_command(&args, &args, &context, &client)
+        .and_then(|command| command.execute())
+        .expect("Command execution failed");
+}
+
+/// Everything commands are created from that stays the same for the whole process.
+struct Context<'a> {
+    transaction_family: &'a TransactionFamily,
+    format_registry: &'a FormatRegistry,
+    payload_format: &'a dyn payloads::Format,
+    message_types: &'a MessageTypeRegistry,
+    component_factory: &'a GeneralPurposeComponentFactory<'a>
+}
 
-    let command: Box<dyn SawtoothCommand> = match args.subcommand() {
-        ("batch", Some(args)) => match args.subcommand() {
-            ("create", Some(args)) => Box::new(batch::CreateCommand::new(client, create_alica_message(&args))),
+/// `command_args` are those of the process or of a console line, `args` always those of the process.
+fn create_command<'a>(args: &clap::ArgMatches, command_args: &clap::ArgMatches, context: &Context<'a>,
+                      client: &dyn Fn() -> Client<'a>) -> Result<Box<dyn SawtoothCommand + 'a>, command::Error> {
+    Ok(match command_args.subcommand() {
+        ("batch", Some(batch_args)) => match batch_args.subcommand() {
+            ("create", Some(create_args)) => {
+                let validators = create_validators(args, create_args, context.payload_format, context.message_types);
+                let command = batch::CreateCommand::new(client(), create_alica_message(create_args), validators)
+                    .with_options(create_transaction_options(create_args));
+                if args.is_present("idempotent") {
+                    let duplicate_check = batch::DuplicateCheck::new(context.transaction_family, context.payload_format);
+                    Box::new(command.with_duplicate_check(duplicate_check))
+                } else {
+                    Box::new(command)
+                }
+            },
+            ("wrap", Some(wrap_args)) => {
+                let inputs = wrap_args.values_of("files").expect("Transaction files missing")
+                    .map(|path| read_input(path))
+                    .collect();
+                Box::new(batch::WrapCommand::new(client(), inputs))
+            },
+            ("import", Some(import_args)) => {
+                let validators = create_validators(args, import_args, context.payload_format, context.message_types);
+                let input = read_input(import_args.value_of("file").expect("Import file missing"));
+                let input = String::from_utf8(input).expect("Import file is not valid UTF-8");
+                let command = batch::ImportCommand::new(client(), input, validators);
+                if args.is_present("idempotent") {
+                    let duplicate_check = batch::DuplicateCheck::new(context.transaction_family, context.payload_format);
+                    Box::new(command.with_duplicate_check(duplicate_check))
+                } else {
+                    Box::new(command)
+                }
+            },
             ("", _) => panic!("No subcommand supplied to batch"),
             (cmd, _) => panic!("No subcommand {} exists for batch", cmd)
         },
-        ("state", Some(args)) => match args.subcommand() {
-            ("list", Some(args)) => {
-                let filters = create_filters(&args);
-                Box::new(state::ListCommand::new(client, &transaction_family.calculate_namespace(),
-                                                 &payload_format, filters))
+        ("state", Some(state_args)) => match state_args.subcommand() {
+            ("list", Some(list_args)) => {
+                let filters = create_filters(&list_args)?;
+                let namespace = context.transaction_family.calculate_namespace();
+                let command = if list_args.is_present("detect_format") {
+                    state::ListCommand::with_format_detection(client(), &namespace, context.format_registry, filters)
+                } else {
+                    state::ListCommand::new(client(), &namespace, context.payload_format, filters)
+                };
+                let command = command.with_output_format(select_output_format(&list_args));
+                let command = match list_args.value_of("at_block") {
+                    Some(block) => command.with_block(block),
+                    None => command
+                };
+                if list_args.is_present("check_integrity") {
+                    Box::new(command.with_integrity_check(context.transaction_family))
+                } else {
+                    Box::new(command)
+                }
+            },
+            ("get", Some(get_args)) => {
+                let address = get_args.value_of("address").expect("Address missing");
+                let command = state::GetCommand::new(client(), address, context.payload_format)
+                    .with_output_format(select_output_format(&get_args));
+                match get_args.value_of("at_block") {
+                    Some(block) => Box::new(command.with_block(block)),
+                    None => Box::new(command)
+                }
+            },
+            ("diff", Some(diff_args)) => {
+                let from_block = diff_args.value_of("from_block").expect("Start block missing");
+                let to_block = diff_args.value_of("to_block").expect("End block missing");
+                let namespace = context.transaction_family.calculate_namespace();
+                Box::new(state::DiffCommand::new(client(), &namespace, context.payload_format, create_filters(&diff_args)?,
+                                                 from_block, to_block)
+                    .with_output_format(select_output_format(&diff_args)))
             },
             ("", _) => panic!("No subcommand supplied to state"),
             (cmd, _) => panic!("No subcommand {} exists for state", cmd),
         },
+        ("transaction", Some(transaction_args)) => match transaction_args.subcommand() {
+            ("list", Some(list_args)) => {
+                let filters = create_filters(&list_args)?;
+                let version_formats = create_version_formats(args, context.format_registry, context.payload_format);
+                let command = transaction::ListCommand::new(client(), &context.transaction_family.name, version_formats, filters);
+                Box::new(command.with_output_format(select_output_format(&list_args)))
+            },
+            ("create", Some(create_args)) => {
+                let validators = create_validators(args, create_args, context.payload_format, context.message_types);
+                let output_path = create_args.value_of("transaction_file").unwrap_or("-");
+                let command = transaction::CreateCommand::new(context.component_factory, create_alica_message(create_args),
+                                                              validators, output_path);
+                Box::new(command.with_options(create_transaction_options(create_args)))
+            },
+            ("", _) => panic!("No subcommand supplied to transaction"),
+            (cmd, _) => panic!("No subcommand {} exists for transaction", cmd),
+        },
+        ("receipt", Some(receipt_args)) => match receipt_args.subcommand() {
+            ("show", Some(show_args)) => {
+                let transaction_ids = show_args.values_of("transaction_ids").expect("Transaction IDs missing")
+                    .map(|transaction_id| transaction_id.to_string())
+                    .collect();
+                let command = receipt::ShowCommand::new(client(), transaction_ids, context.payload_format);
+                Box::new(command.with_output_format(select_output_format(&show_args)))
+            },
+            ("", _) => panic!("No subcommand supplied to receipt"),
+            (cmd, _) => panic!("No subcommand {} exists for receipt", cmd),
+        },
+        ("network", Some(network_args)) => match network_args.subcommand() {
+            ("status", Some(status_args)) => {
+                let family_version = select_family_version(args, context.transaction_family);
+                let command = network::StatusCommand::new(client(), &context.transaction_family.name, &family_version);
+                Box::new(command.with_output_format(select_output_format(&status_args)))
+            },
+            ("", _) => panic!("No subcommand supplied to network"),
+            (cmd, _) => panic!("No subcommand {} exists for network", cmd),
+        },
+        ("dashboard", Some(dashboard_args)) => {
+            let poll_interval = dashboard_args.value_of("poll_interval").unwrap_or("1000")
+                .parse::<u64>().expect("Interval is not an integer");
+            let silence_threshold = dashboard_args.value_of("silence_threshold").unwrap_or("10")
+                .parse::<u64>().expect("Silence threshold is not an integer");
+            let namespace = context.transaction_family.calculate_namespace();
+            Box::new(dashboard::DashboardCommand::new(client(), &namespace, context.payload_format)
+                .with_poll_interval(Duration::from_millis(poll_interval))
+                .with_silence_threshold(Duration::from_secs(silence_threshold)))
+        },
+        ("export", Some(export_args)) => {
+            let directory = PathBuf::from(export_args.value_of("directory").expect("Output directory missing"));
+            let filter_expressions = export_args.values_of("filter")
+                .map_or_else(Vec::new, |values| values.map(|value| value.to_string()).collect());
+            let namespace = context.transaction_family.calculate_namespace();
+            Box::new(export::ExportCommand::new(client(), &namespace, context.payload_format, create_filters(&export_args)?,
+                                                &directory)
+                .with_filter_expressions(filter_expressions))
+        },
+        ("replay", Some(replay_args)) => {
+            let source = match replay_args.value_of("export_file") {
+                Some(export_file) => replay::ReplaySource::Export(PathBuf::from(export_file)),
+                None => replay::ReplaySource::Network(client(), context.transaction_family.calculate_namespace(),
+                                                      context.payload_format)
+            };
+            let routes = create_replay_routes(&replay_args);
+            let publisher = ZmqPublisher::bind(&routes.endpoints()).expect("Could not bind the publishing endpoints");
+            let speed = replay_args.value_of("speed").unwrap_or("1.0").parse::<f64>().expect("Speed is not a number");
+            if speed.is_nan() || speed <= 0.0 {
+                panic!("Speed has to be greater than 0");
+            }
+            let start_offset = replay_args.value_of("start_offset").unwrap_or("0")
+                .parse::<u64>().expect("Start offset is not an integer");
+            let join_delay = replay_args.value_of("join_delay").unwrap_or("1000")
+                .parse::<u64>().expect("Join delay is not an integer");
+            let command = replay::ReplayCommand::new(source, create_filters(&replay_args)?, routes, Box::new(publisher))
+                .with_speed(speed)
+                .with_start_offset(Duration::from_millis(start_offset))
+                .with_timestamp_unit(select_timestamp_unit(&replay_args))
+                .with_join_delay(Duration::from_millis(join_delay));
+            if replay_args.is_present("loop") {
+                Box::new(command.with_loop())
+            } else {
+                Box::new(command)
+            }
+        },
+        ("record", Some(record_args)) => {
+            if args.is_present("offline") {
+                panic!("The record command submits to the sawtooth network and can not run offline");
+            }
+            let endpoints: Vec<&str> = record_args.values_of("endpoint").expect("Endpoints missing").collect();
+            let subscriber = ZmqSubscriber::connect(&endpoints).expect("Could not connect to the endpoints");
+            let spool_directory = PathBuf::from(record_args.value_of("spool").expect("Spool directory missing"));
+            let validators = create_validators(args, record_args, context.payload_format, context.message_types);
+            let command = record::RecordCommand::new(client(), Box::new(subscriber), create_filters(&record_args)?,
+                                                     validators, &spool_directory);
+            Box::new(configure_recorder(&record_args, command))
+        },
+        ("sync", Some(_)) => {
+            if args.is_present("offline") {
+                panic!("The sync command reads from the sawtooth network and can not run offline");
+            }
+            let mirror_path = PathBuf::from(args.value_of("mirror").expect("Mirror directory missing"));
+            let mirror = Mirror::open(&mirror_path).expect("Could not load mirror");
+            let namespace = context.transaction_family.calculate_namespace();
+            Box::new(sync::SyncCommand::new(client(), mirror, &context.transaction_family.name, &namespace))
+        },
+        ("verify", Some(verify_args)) => match verify_args.subcommand() {
+            ("batches", Some(batches_args)) => {
+                let source = match batches_args.value_of("batch_file") {
+                    Some(batch_file) => verify::BatchSource::File(PathBuf::from(batch_file)),
+                    None => verify::BatchSource::Network(client())
+                };
+                Box::new(verify::BatchesCommand::new(source).with_output_format(select_output_format(&batches_args)))
+            },
+            ("transactions", Some(transactions_args)) =>
+                Box::new(verify::TransactionsCommand::new(client()).with_output_format(select_output_format(&transactions_args))),
+            ("", _) => panic!("No subcommand supplied to verify"),
+            (cmd, _) => panic!("No subcommand {} exists for verify", cmd),
+        },
+        ("address", Some(address_args)) => create_address_command(address_args, context.transaction_family),
         ("", _) => panic!("No subcommand supplied"),
         (cmd, _) => panic!("No subcommand {} exists", cmd)
+    })
+}
+
+fn create_batch_file_verification<'a>(args: &clap::ArgMatches) -> Option<Box<dyn SawtoothCommand + 'a>> {
+    let batches_args = match args.subcommand() {
+        ("verify", Some(verify_args)) => verify_args.subcommand_matches("batches")?,
+        _ => return None
     };
+    let batch_file = batches_args.value_of("batch_file")?;
+    Some(Box::new(verify::BatchesCommand::new(verify::BatchSource::File(PathBuf::from(batch_file)))
+        .with_output_format(select_output_format(batches_args))))
+}
 
-    command.execute().expect("Command execution failed");
+fn create_address_command<'a>(address_args: &clap::ArgMatches, transaction_family: &'a TransactionFamily)
+                              -> Box<dyn SawtoothCommand + 'a> {
+    match address_args.subcommand() {
+        ("namespace", Some(_)) => Box::new(address::NamespaceCommand::new(transaction_family)),
+        ("compute", Some(args)) => {
+            let agent_id = args.value_of("agent_id").expect("agent id missing");
+            let message_type = args.value_of("message_type").expect("message type missing");
+            let timestamp = args.value_of("timestamp").expect("timestamp missing")
+                .parse::<u64>().expect("Timestamp is not an integer");
+            Box::new(address::ComputeCommand::new(transaction_family, agent_id, message_type, timestamp))
+        },
+        ("parse", Some(args)) => {
+            let address = args.value_of("address").expect("Address missing");
+            Box::new(address::ParseCommand::new(transaction_family, address))
+        },
+        ("", _) => panic!("No subcommand supplied to address"),
+        (cmd, _) => panic!("No subcommand {} exists for address", cmd)
+    }
 }
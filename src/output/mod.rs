@@ -0,0 +1,142 @@
+use serde_json::{json, Map, Value};
+use sawtooth_alica_payload::payloads::TransactionPayload;
+use crate::alica;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum OutputFormat {
+    Text,
+    Json
+}
+
+impl OutputFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            _ => None
+        }
+    }
+}
+
+pub enum MessageBody {
+    Decoded(Value),
+    Text(String),
+    Binary(String)
+}
+
+impl MessageBody {
+    pub fn of(payload: &TransactionPayload) -> Self {
+        if let Some(decoded_message) = alica::decode_message(&payload.message_type, &payload.message_bytes) {
+            return MessageBody::Decoded(decoded_message);
+        }
+
+        match String::from_utf8(payload.message_bytes.clone()) {
+            Ok(text) => MessageBody::Text(text),
+            Err(_) => MessageBody::Binary(data_encoding::HEXLOWER.encode(&payload.message_bytes))
+        }
+    }
+
+    fn encoding(&self) -> &str {
+        match self {
+            MessageBody::Decoded(_) => "capnp",
+            MessageBody::Text(_) => "text",
+            MessageBody::Binary(_) => "hex"
+        }
+    }
+}
+
+pub fn payload_as_json(payload: &TransactionPayload) -> Map<String, Value> {
+    let message_body = MessageBody::of(payload);
+    let message = match &message_body {
+        MessageBody::Decoded(decoded_message) => decoded_message.clone(),
+        MessageBody::Text(text) => Value::from(text.as_str()),
+        MessageBody::Binary(hex) => Value::from(hex.as_str())
+    };
+
+    let mut json_payload = Map::new();
+    json_payload.insert("agent_id".to_string(), json!(payload.agent_id));
+    json_payload.insert("message_type".to_string(), json!(payload.message_type));
+    json_payload.insert("message".to_string(), message);
+    json_payload.insert("message_encoding".to_string(), json!(message_body.encoding()));
+    json_payload.insert("timestamp".to_string(), json!(payload.timestamp));
+    json_payload
+}
+
+pub fn print_json(values: Vec<Value>) {
+    print_json_value(&Value::Array(values));
+}
+
+pub fn print_json_value(value: &Value) {
+    println!("{}", serde_json::to_string_pretty(value).expect("JSON output can not be rendered"));
+}
+
+pub fn print_payload(payload: &TransactionPayload) {
+    println!("-> Agent ID: \"{}\"", &payload.agent_id);
+    println!("-> Message Type: \"{}\"", &payload.message_type);
+    match MessageBody::of(payload) {
+        MessageBody::Decoded(decoded_message) => {
+            println!("-> Message (Cap'n Proto):");
+            for line in render_value(&decoded_message, 1) {
+                println!("{}", line);
+            }
+        },
+        MessageBody::Text(text) => println!("-> Message: \"{}\"", text),
+        MessageBody::Binary(hex) => println!("-> Message (hex): {}", hex)
+    }
+    println!("-> Timestamp of sending: \"{}\"", payload.timestamp)
+}
+
+fn render_value(value: &Value, depth: usize) -> Vec<String> {
+    let indentation = "   ".repeat(depth);
+    match value {
+        Value::Object(fields) => fields.iter()
+            .flat_map(|(name, field)| match field {
+                Value::Object(_) | Value::Array(_) => {
+                    let mut lines = vec![format!("{}{}:", indentation, name)];
+                    lines.extend(render_value(field, depth + 1));
+                    lines
+                },
+                _ => vec![format!("{}{}: {}", indentation, name, field)]
+            })
+            .collect(),
+        Value::Array(elements) if elements.is_empty() => vec![format!("{}(empty)", indentation)],
+        Value::Array(elements) => elements.iter()
+            .enumerate()
+            .flat_map(|(index, element)| match element {
+                Value::Object(_) | Value::Array(_) => {
+                    let mut lines = vec![format!("{}[{}]", indentation, index)];
+                    lines.extend(render_value(element, depth + 1));
+                    lines
+                },
+                _ => vec![format!("{}[{}] {}", indentation, index, element)]
+            })
+            .collect(),
+        _ => vec![format!("{}{}", indentation, value)]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sawtooth_alica_payload::payloads::TransactionPayload;
+    use crate::output::payload_as_json;
+
+    #[test]
+    fn it_falls_back_to_hex_for_binary_messages() {
+        let payload = TransactionPayload::new("agent1", "RoleSwitch", &[0xff, 0x00, 0x13], 69182798179);
+
+        let json_payload = payload_as_json(&payload);
+
+        assert_eq!(json_payload["message"], "ff0013");
+        assert_eq!(json_payload["message_encoding"], "hex");
+    }
+
+    #[test]
+    fn it_keeps_text_messages_readable() {
+        let payload = TransactionPayload::new("agent1", "CustomInfo", "message".as_bytes(), 69182798179);
+
+        let json_payload = payload_as_json(&payload);
+
+        assert_eq!(json_payload["message"], "message");
+        assert_eq!(json_payload["message_encoding"], "text");
+    }
+}
@@ -1,47 +1,155 @@
-use sawtooth_sdk::messaging::stream::{MessageConnection, MessageSender};
-use sawtooth_sdk::messaging::zmq_stream::ZmqMessageConnection;
+use std::rc::Rc;
 use sawtooth_sdk::messages::transaction::Transaction;
+use sawtooth_sdk::messages::batch::Batch;
 use sawtooth_sdk::messages::validator::{self, Message_MessageType};
-use sawtooth_sdk::messages::client_state::{ClientStateListRequest, ClientStateListResponse, ClientStateListResponse_Entry};
+use sawtooth_sdk::messages::client_state::{ClientStateListRequest, ClientStateListResponse, ClientStateListResponse_Entry,
+                                           ClientStateListResponse_Status, ClientStateGetRequest, ClientStateGetResponse,
+                                           ClientStateGetResponse_Status};
 use sawtooth_sdk::messages::client_batch_submit::{ClientBatchSubmitRequest, ClientBatchSubmitResponse, ClientBatchSubmitResponse_Status};
 use sawtooth_sdk::messages::client_transaction::{ClientTransactionListRequest, ClientTransactionListResponse};
+use sawtooth_sdk::messages::client_batch::{ClientBatchListRequest, ClientBatchListResponse};
+use sawtooth_sdk::messages::client_receipt::{ClientReceiptGetRequest, ClientReceiptGetResponse, ClientReceiptGetResponse_Status};
+use sawtooth_sdk::messages::transaction_receipt::TransactionReceipt;
+use sawtooth_sdk::messages::client_status::{ClientStatusGetRequest, ClientStatusGetResponse, ClientStatusGetResponse_Status};
+use sawtooth_sdk::messages::client_peers::{ClientPeersGetRequest, ClientPeersGetResponse, ClientPeersGetResponse_Status};
+use sawtooth_sdk::messages::client_block::{ClientBlockListRequest, ClientBlockListResponse, ClientBlockListResponse_Status,
+                                           ClientBlockGetByIdRequest, ClientBlockGetByNumRequest, ClientBlockGetResponse,
+                                           ClientBlockGetResponse_Status};
+use sawtooth_sdk::messages::client_list_control::ClientPagingControls;
+use sawtooth_sdk::messages::block::Block;
 use protobuf::ProtobufEnum;
-use crate::sawtooth::Error::{SerializationError, WrongResponse, DeserializationError, RequestError, ResponseError, InvalidBatch, BatchStatusUnset, InternalError, FullQueue};
-use crate::sawtooth::{Error, TransactionPayload, ComponentFactory};
+use crate::sawtooth::Error::{SerializationError, WrongResponse, DeserializationError, InvalidBatch, BatchStatusUnset, InternalError, FullQueue,
+                             VerificationError, StateUnavailable, ReceiptsUnavailable,
+                             NetworkStatusUnavailable, BlocksUnavailable};
+use crate::sawtooth::{Error, TransactionPayload, TransactionOptions, ComponentFactory, verification};
+use crate::sawtooth::transport::{self, Transport};
 
+/// Clones share the transport, so they use the same connection or simulated ledger.
+#[derive(Clone)]
 pub struct Client<'a> {
     factory: &'a dyn ComponentFactory,
-    sender: Box<dyn MessageSender>,
+    transport: Rc<dyn Transport + 'a>,
 }
 
 impl<'a> Client<'a> {
-    pub fn new(url: &str, component_factory: &'a dyn ComponentFactory) -> Self {
-        let connection = ZmqMessageConnection::new(url).create();
+    pub fn new(url: &str, component_factory: &'a dyn ComponentFactory) -> Result<Self, Error> {
+        let transport = transport::connect(url)?;
+        Ok(Client::with_transport(transport, component_factory))
+    }
+
+    pub fn with_transport(transport: Box<dyn Transport + 'a>, component_factory: &'a dyn ComponentFactory) -> Self {
         Client {
             factory: component_factory,
-            sender: Box::from(connection.0),
+            transport: Rc::from(transport),
         }
     }
 
     pub fn list_state_entries(&self) -> Result<Vec<ClientStateListResponse_Entry>, Error> {
-        let request = ClientStateListRequest::new();
-        let response = self.send(&request, Message_MessageType::CLIENT_STATE_LIST_REQUEST)?;
-        self.validate_response(&response, Message_MessageType::CLIENT_STATE_LIST_RESPONSE)?;
-        let response_data = self.parse_response::<ClientStateListResponse>(response)?;
-        Ok(response_data.get_entries().to_vec())
+        self.list_state_entries_at("")
+    }
+
+    /// Lists the state entries at the state root of a block page by page, an empty state root lists the current ones.
+    pub fn list_state_entries_at(&self, state_root: &str) -> Result<Vec<ClientStateListResponse_Entry>, Error> {
+        let mut entries = Vec::new();
+        let mut state_root = state_root.to_string();
+        let mut start = String::new();
+        loop {
+            let mut paging = ClientPagingControls::new();
+            paging.set_start(start);
+            let mut request = ClientStateListRequest::new();
+            request.set_state_root(state_root.clone());
+            request.set_paging(paging);
+            let response = self.send(&request, Message_MessageType::CLIENT_STATE_LIST_REQUEST)?;
+            self.validate_response(&response, Message_MessageType::CLIENT_STATE_LIST_RESPONSE)?;
+            let response_data = self.parse_response::<ClientStateListResponse>(response)?;
+
+            match response_data.get_status() {
+                ClientStateListResponse_Status::OK => entries.extend(response_data.get_entries().iter().cloned()),
+                ClientStateListResponse_Status::NO_ROOT | ClientStateListResponse_Status::NOT_READY
+                    if state_root.is_empty() => return Ok(entries),
+                status => return Err(StateUnavailable(status.descriptor().name().to_string()))
+            }
+
+            start = response_data.get_paging().get_next().to_string();
+            if start.is_empty() {
+                return Ok(entries);
+            }
+            if state_root.is_empty() {
+                state_root = response_data.get_state_root().to_string();
+            }
+        }
+    }
+
+    /// Returns the data stored at the address or None if nothing is stored there.
+    pub fn get_state(&self, address: &str) -> Result<Option<Vec<u8>>, Error> {
+        self.get_state_at(address, "")
+    }
+
+    /// An empty state root reads the current state.
+    pub fn get_state_at(&self, address: &str, state_root: &str) -> Result<Option<Vec<u8>>, Error> {
+        let mut request = ClientStateGetRequest::new();
+        request.set_address(address.to_string());
+        request.set_state_root(state_root.to_string());
+        let response = self.send(&request, Message_MessageType::CLIENT_STATE_GET_REQUEST)?;
+        self.validate_response(&response, Message_MessageType::CLIENT_STATE_GET_RESPONSE)?;
+        let response_data = self.parse_response::<ClientStateGetResponse>(response)?;
+
+        match response_data.get_status() {
+            ClientStateGetResponse_Status::OK => Ok(Some(response_data.get_value().to_vec())),
+            ClientStateGetResponse_Status::NO_RESOURCE => Ok(None),
+            status => Err(StateUnavailable(status.descriptor().name().to_string()))
+        }
     }
 
     pub fn create_batch(&self, contents: &[&TransactionPayload]) -> Result<(), Error> {
+        let options = TransactionOptions::default();
+        let contents: Vec<(&TransactionPayload, &TransactionOptions)> = contents.iter()
+            .map(|message| (*message, &options))
+            .collect();
+        self.create_batch_with_options(&contents).map(|_| ())
+    }
+
+    /// Submits the messages as a single batch and returns the IDs of the created transactions.
+    pub fn create_batch_with_options(&self, contents: &[(&TransactionPayload, &TransactionOptions)])
+                                     -> Result<Vec<String>, Error> {
+        let transactions = self.create_transactions(contents)?;
+        let batch = self.batch_of(&transactions)?;
+        self.submit_batch(batch)?;
+        Ok(transaction_ids_of(&transactions))
+    }
+
+    /// Creates and signs the transactions for the messages without submitting them.
+    pub fn create_transactions(&self, contents: &[(&TransactionPayload, &TransactionOptions)])
+                               -> Result<Vec<Transaction>, Error> {
         let mut transactions = Vec::new();
         transactions.reserve(contents.len());
-        for message in contents {
-            let transaction_header = self.factory.create_transaction_header_for(message)?;
+        for (message, options) in contents {
+            let transaction_header = self.factory.create_transaction_header_with(message, options)?;
             let transaction = self.factory.create_transaction_for(message, &transaction_header)?;
             transactions.push(transaction);
         }
-        let batch_header = self.factory.create_batch_header_for(&transactions)?;
-        let batch = self.factory.create_batch_for(&transactions, &batch_header)?;
+        Ok(transactions)
+    }
+
+    /// Verifies transactions that were signed elsewhere and wraps them into a batch.
+    pub fn submit_transactions(&self, transactions: Vec<Transaction>) -> Result<Vec<String>, Error> {
+        let batch = self.batch_of(&transactions)?;
+        let violations = verification::verify_batch(&batch);
+        if !violations.is_empty() {
+            let descriptions: Vec<String> = violations.iter().map(|violation| violation.description()).collect();
+            return Err(VerificationError(descriptions.join(", ")));
+        }
+
+        self.submit_batch(batch)?;
+        Ok(transaction_ids_of(&transactions))
+    }
 
+    fn batch_of(&self, transactions: &Vec<Transaction>) -> Result<Batch, Error> {
+        let batch_header = self.factory.create_batch_header_for(transactions)?;
+        self.factory.create_batch_for(transactions, &batch_header)
+    }
+
+    fn submit_batch(&self, batch: Batch) -> Result<(), Error> {
         let mut batch_submit_request = ClientBatchSubmitRequest::new();
         batch_submit_request.set_batches(protobuf::RepeatedField::from_vec(vec![batch]));
 
@@ -66,15 +174,121 @@ impl<'a> Client<'a> {
         Ok(response_data.get_transactions().to_vec())
     }
 
+    pub fn list_batches(&self) -> Result<Vec<Batch>, Error> {
+        let request = ClientBatchListRequest::new();
+        let response = self.send(&request, Message_MessageType::CLIENT_BATCH_LIST_REQUEST)?;
+        self.validate_response(&response, Message_MessageType::CLIENT_BATCH_LIST_RESPONSE)?;
+        let response_data = self.parse_response::<ClientBatchListResponse>(response)?;
+        Ok(response_data.get_batches().to_vec())
+    }
+
+    /// Returns the receipts of the transactions, which fails if any of them is not committed yet.
+    pub fn get_receipts(&self, transaction_ids: &[String]) -> Result<Vec<TransactionReceipt>, Error> {
+        let mut request = ClientReceiptGetRequest::new();
+        request.set_transaction_ids(protobuf::RepeatedField::from_vec(transaction_ids.to_vec()));
+        let response = self.send(&request, Message_MessageType::CLIENT_RECEIPT_GET_REQUEST)?;
+        self.validate_response(&response, Message_MessageType::CLIENT_RECEIPT_GET_RESPONSE)?;
+        let response_data = self.parse_response::<ClientReceiptGetResponse>(response)?;
+
+        match response_data.get_status() {
+            ClientReceiptGetResponse_Status::OK => Ok(response_data.get_receipts().to_vec()),
+            status => Err(ReceiptsUnavailable(status.descriptor().name().to_string()))
+        }
+    }
+
+    pub fn get_status(&self) -> Result<ClientStatusGetResponse, Error> {
+        let request = ClientStatusGetRequest::new();
+        let response = self.send(&request, Message_MessageType::CLIENT_STATUS_GET_REQUEST)?;
+        self.validate_response(&response, Message_MessageType::CLIENT_STATUS_GET_RESPONSE)?;
+        let response_data = self.parse_response::<ClientStatusGetResponse>(response)?;
+
+        match response_data.get_status() {
+            ClientStatusGetResponse_Status::OK => Ok(response_data),
+            status => Err(NetworkStatusUnavailable(status.descriptor().name().to_string()))
+        }
+    }
+
+    pub fn get_peers(&self) -> Result<Vec<String>, Error> {
+        let request = ClientPeersGetRequest::new();
+        let response = self.send(&request, Message_MessageType::CLIENT_PEERS_GET_REQUEST)?;
+        self.validate_response(&response, Message_MessageType::CLIENT_PEERS_GET_RESPONSE)?;
+        let response_data = self.parse_response::<ClientPeersGetResponse>(response)?;
+
+        match response_data.get_status() {
+            ClientPeersGetResponse_Status::OK => Ok(response_data.get_peers().to_vec()),
+            status => Err(NetworkStatusUnavailable(status.descriptor().name().to_string()))
+        }
+    }
+
+    /// Returns the newest block of the chain or None if the validator has no genesis block yet.
+    pub fn get_chain_head(&self) -> Result<Option<Block>, Error> {
+        let mut paging = ClientPagingControls::new();
+        paging.set_limit(1);
+        let mut request = ClientBlockListRequest::new();
+        request.set_paging(paging);
+        let response = self.send(&request, Message_MessageType::CLIENT_BLOCK_LIST_REQUEST)?;
+        self.validate_response(&response, Message_MessageType::CLIENT_BLOCK_LIST_RESPONSE)?;
+        let response_data = self.parse_response::<ClientBlockListResponse>(response)?;
+
+        match response_data.get_status() {
+            ClientBlockListResponse_Status::OK => Ok(response_data.get_blocks().first().cloned()),
+            ClientBlockListResponse_Status::NO_ROOT | ClientBlockListResponse_Status::NOT_READY => Ok(None),
+            status => Err(NetworkStatusUnavailable(status.descriptor().name().to_string()))
+        }
+    }
+
+    /// Looks the block up by number if the reference is one and by ID otherwise.
+    pub fn get_block(&self, reference: &str) -> Result<Option<Block>, Error> {
+        let response = match reference.parse::<u64>() {
+            Ok(block_num) => {
+                let mut request = ClientBlockGetByNumRequest::new();
+                request.set_block_num(block_num);
+                self.send(&request, Message_MessageType::CLIENT_BLOCK_GET_BY_NUM_REQUEST)?
+            },
+            Err(_) => {
+                let mut request = ClientBlockGetByIdRequest::new();
+                request.set_block_id(reference.to_string());
+                self.send(&request, Message_MessageType::CLIENT_BLOCK_GET_BY_ID_REQUEST)?
+            }
+        };
+        self.validate_response(&response, Message_MessageType::CLIENT_BLOCK_GET_RESPONSE)?;
+        let response_data = self.parse_response::<ClientBlockGetResponse>(response)?;
+
+        match response_data.get_status() {
+            ClientBlockGetResponse_Status::OK => Ok(Some(response_data.get_block().clone())),
+            ClientBlockGetResponse_Status::NO_RESOURCE => Ok(None),
+            status => Err(BlocksUnavailable(status.descriptor().name().to_string()))
+        }
+    }
+
+    /// Returns blocks from the chain head or start, newest first, and the start of the next older page.
+    pub fn list_blocks(&self, start: Option<&str>, limit: i32) -> Result<(Vec<Block>, Option<String>), Error> {
+        let mut paging = ClientPagingControls::new();
+        paging.set_limit(limit);
+        if let Some(start) = start {
+            paging.set_start(start.to_string());
+        }
+        let mut request = ClientBlockListRequest::new();
+        request.set_paging(paging);
+        let response = self.send(&request, Message_MessageType::CLIENT_BLOCK_LIST_REQUEST)?;
+        self.validate_response(&response, Message_MessageType::CLIENT_BLOCK_LIST_RESPONSE)?;
+        let response_data = self.parse_response::<ClientBlockListResponse>(response)?;
+
+        match response_data.get_status() {
+            ClientBlockListResponse_Status::OK => {
+                let next = response_data.get_paging().get_next();
+                let next = if next.is_empty() { None } else { Some(next.to_string()) };
+                Ok((response_data.get_blocks().to_vec(), next))
+            },
+            ClientBlockListResponse_Status::NO_ROOT => Ok((Vec::new(), None)),
+            status => Err(BlocksUnavailable(status.descriptor().name().to_string()))
+        }
+    }
+
     pub fn send(&self, request: &dyn protobuf::Message, request_type: Message_MessageType)
                 -> Result<validator::Message, Error> {
-        let correlation_id = uuid::Uuid::new_v4().to_string();
         let message_bytes = &request.write_to_bytes().map_err(|_| SerializationError("Request".to_string()))?;
-
-        self.sender.send(request_type, &correlation_id, message_bytes)
-            .map(|mut future| future.get())
-            .map_err(|_| ResponseError)?
-            .map_err(|_| RequestError)
+        self.transport.send(request_type, message_bytes)
     }
 
     fn validate_response(&self, response: &validator::Message, expected_type: Message_MessageType) -> Result<(), Error> {
@@ -94,8 +308,8 @@ impl<'a> Client<'a> {
     }
 }
 
-impl<'a> Drop for Client<'a> {
-    fn drop(&mut self) {
-        self.sender.close();
-    }
-}
\ No newline at end of file
+fn transaction_ids_of(transactions: &[Transaction]) -> Vec<String> {
+    transactions.iter()
+        .map(|transaction| transaction.get_header_signature().to_string())
+        .collect()
+}
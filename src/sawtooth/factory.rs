@@ -1,24 +1,91 @@
 use protobuf::Message;
 use sawtooth_sdk::messages::transaction::{Transaction, TransactionHeader};
 use sawtooth_sdk::messages::batch::{Batch, BatchHeader};
-use crate::sawtooth::{TransactionFactory, TransactionPayload, Error, BatchFactory, ComponentFactory};
+use crate::sawtooth::{TransactionFactory, TransactionOptions, TransactionPayload, Error, BatchFactory, ComponentFactory};
 use crate::sawtooth::Error::{SerializationError, SigningError, KeyError};
 use crate::sawtooth::helper;
 use sawtooth_sdk::signing::Signer;
 use sawtooth_alica_payload::{payloads, TransactionFamily};
 
+/// Key that signs the batches wrapping the transactions of a factory.
+enum Batcher<'a> {
+    TransactionSigner,
+    Own(Signer<'a>),
+    Remote(String)
+}
+
+/// How the nonce is chosen for transactions without an idempotency key.
+pub enum NonceStrategy {
+    Random,
+    /// Resubmitting a message recreates the same transaction, which the validator rejects as a duplicate.
+    Derived
+}
+
 pub struct GeneralPurposeComponentFactory<'a> {
     transaction_family: &'a TransactionFamily,
+    family_version: String,
     payload_format: &'a dyn payloads::Format,
-    signer: Signer<'a>
+    signer: Signer<'a>,
+    batcher: Batcher<'a>,
+    nonce_strategy: NonceStrategy
 }
 
 impl<'a> GeneralPurposeComponentFactory<'a> {
     pub fn new(transaction_family: &'a TransactionFamily, payload_format: &'a dyn payloads::Format, signer: Signer<'a>) -> Self {
         GeneralPurposeComponentFactory {
             transaction_family,
+            family_version: transaction_family.latest_version().clone(),
             payload_format,
-            signer
+            signer,
+            batcher: Batcher::TransactionSigner,
+            nonce_strategy: NonceStrategy::Random
+        }
+    }
+
+    pub fn with_family_version(mut self, family_version: &str) -> Self {
+        self.family_version = family_version.to_string();
+        self
+    }
+
+    /// Signs batches with a key of their own instead of the key that signs the transactions.
+    pub fn with_batcher(mut self, batcher: Signer<'a>) -> Self {
+        self.batcher = Batcher::Own(batcher);
+        self
+    }
+
+    /// Names a batcher that only the public key is known of. Such a factory cannot create batches itself.
+    pub fn with_remote_batcher(mut self, batcher_public_key: &str) -> Self {
+        self.batcher = Batcher::Remote(batcher_public_key.to_string());
+        self
+    }
+
+    pub fn with_nonce_strategy(mut self, nonce_strategy: NonceStrategy) -> Self {
+        self.nonce_strategy = nonce_strategy;
+        self
+    }
+
+    fn nonce_for(&self, serialized_payload: &[u8], options: &TransactionOptions) -> String {
+        match (&options.idempotency_key, &self.nonce_strategy) {
+            (Some(idempotency_key), _) => helper::calculate_checksum(&idempotency_key.as_bytes()),
+            (None, NonceStrategy::Derived) => helper::calculate_checksum(&serialized_payload),
+            (None, NonceStrategy::Random) => helper::random_nonce()
+        }
+    }
+
+    fn batcher_public_key(&self, component: &str) -> Result<String, Error> {
+        let public_key = match &self.batcher {
+            Batcher::TransactionSigner => self.signer.get_public_key(),
+            Batcher::Own(batcher) => batcher.get_public_key(),
+            Batcher::Remote(public_key) => return Ok(public_key.clone())
+        };
+        public_key.map(|public_key| public_key.as_hex()).map_err(|_| KeyError(component.to_string()))
+    }
+
+    fn batch_signer(&self) -> Result<&Signer<'a>, Error> {
+        match &self.batcher {
+            Batcher::TransactionSigner => Ok(&self.signer),
+            Batcher::Own(batcher) => Ok(batcher),
+            Batcher::Remote(_) => Err(KeyError("Batch Header".to_string()))
         }
     }
 }
@@ -39,8 +106,12 @@ impl<'a> TransactionFactory for GeneralPurposeComponentFactory<'a> {
         Ok(transaction)
     }
 
-    fn create_transaction_header_for(&self, message: &TransactionPayload)
-                              -> Result<TransactionHeader, Error> {
+    fn create_transaction_header_for(&self, message: &TransactionPayload) -> Result<TransactionHeader, Error> {
+        self.create_transaction_header_with(message, &TransactionOptions::default())
+    }
+
+    fn create_transaction_header_with(&self, message: &TransactionPayload, options: &TransactionOptions)
+                                      -> Result<TransactionHeader, Error> {
         let serialized_payload = self.payload_format.serialize(message)
             .map_err(|_| SerializationError("Transaction Payload".to_string()))?;
         let payload_checksum = helper::calculate_checksum(&serialized_payload);
@@ -49,12 +120,13 @@ impl<'a> TransactionFactory for GeneralPurposeComponentFactory<'a> {
 
         let mut transaction_header = TransactionHeader::new();
         transaction_header.set_family_name(self.transaction_family.name.clone());
-        transaction_header.set_family_version(self.transaction_family.latest_version().clone());
-        transaction_header.set_nonce(helper::random_nonce());
-        transaction_header.set_inputs(protobuf::RepeatedField::from_vec(vec![state_address.clone()]));
-        transaction_header.set_outputs(protobuf::RepeatedField::from_vec(vec![state_address]));
-        transaction_header.set_signer_public_key(public_key.clone());
-        transaction_header.set_batcher_public_key(public_key);
+        transaction_header.set_family_version(self.family_version.clone());
+        transaction_header.set_nonce(self.nonce_for(&serialized_payload, options));
+        transaction_header.set_inputs(protobuf::RepeatedField::from_vec(with_address(&state_address, &options.inputs)));
+        transaction_header.set_outputs(protobuf::RepeatedField::from_vec(with_address(&state_address, &options.outputs)));
+        transaction_header.set_dependencies(protobuf::RepeatedField::from_vec(options.dependencies.clone()));
+        transaction_header.set_signer_public_key(public_key);
+        transaction_header.set_batcher_public_key(self.batcher_public_key("Transaction Header")?);
         transaction_header.set_payload_sha512(payload_checksum);
 
         Ok(transaction_header)
@@ -64,7 +136,7 @@ impl<'a> TransactionFactory for GeneralPurposeComponentFactory<'a> {
 impl<'a> BatchFactory for GeneralPurposeComponentFactory<'a> {
     fn create_batch_for(&self, transactions: &Vec<Transaction>, header: &BatchHeader) -> Result<Batch, Error> {
         let header = header.write_to_bytes().map_err(|_| SerializationError("Batch Header".to_string()))?;
-        let header_signature = self.signer.sign(&header).map_err(|_| SigningError("Batch Header".to_string()))?;
+        let header_signature = self.batch_signer()?.sign(&header).map_err(|_| SigningError("Batch Header".to_string()))?;
 
         let mut batch = Batch::new();
         batch.set_header_signature(header_signature);
@@ -75,7 +147,7 @@ impl<'a> BatchFactory for GeneralPurposeComponentFactory<'a> {
     }
 
     fn create_batch_header_for(&self, transactions: &Vec<Transaction>) -> Result<BatchHeader, Error> {
-        let public_key = self.signer.get_public_key().map_err(|_| KeyError("Batch Header".to_string()))?.as_hex();
+        let public_key = self.batcher_public_key("Batch Header")?;
 
         let mut header = BatchHeader::new();
         header.set_signer_public_key(public_key);
@@ -91,3 +163,13 @@ impl<'a> BatchFactory for GeneralPurposeComponentFactory<'a> {
 }
 
 impl<'a> ComponentFactory for GeneralPurposeComponentFactory<'a> {}
+
+fn with_address(address: &str, additional_addresses: &[String]) -> Vec<String> {
+    let mut addresses = vec![address.to_string()];
+    for additional_address in additional_addresses {
+        if !addresses.contains(additional_address) {
+            addresses.push(additional_address.clone());
+        }
+    }
+    addresses
+}
@@ -1,5 +1,6 @@
 use rand::Rng;
 use sha2::Digest;
+use sawtooth_sdk::signing;
 
 pub fn random_nonce() -> String {
     let mut nonce = [0u8; 16];
@@ -13,3 +14,37 @@ pub fn calculate_checksum<T>(data: &T) -> String
     hasher.update(data);
     data_encoding::HEXLOWER.encode(&hasher.finalize()[..])
 }
+
+/// Address the settings transaction family stores a setting at.
+pub fn setting_address(key: &str) -> String {
+    let mut parts: Vec<&str> = key.splitn(4, '.').collect();
+    parts.resize(4, "");
+
+    let mut address = String::from("000000");
+    for part in parts {
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(part);
+        address.push_str(&data_encoding::HEXLOWER.encode(&hasher.finalize()[..])[..16]);
+    }
+    address
+}
+
+pub fn verify_signature(signature: &str, message: &[u8], public_key: &str) -> bool {
+    let context = signing::create_context("secp256k1")
+        .expect("This happens only if the sawtooth team has decided to rename or remove the secp256k1 algorithm");
+    match signing::secp256k1::Secp256k1PublicKey::from_hex(public_key) {
+        Ok(public_key) => context.verify(signature, message, &public_key).unwrap_or(false),
+        Err(_) => false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sawtooth::helper;
+
+    #[test]
+    fn it_computes_the_address_of_a_setting_like_the_settings_family() {
+        assert_eq!(helper::setting_address("sawtooth.settings.vote.authorized_keys"),
+                   "000000a87cb5eafdcca6a8cde0fb0dec1400c5ab274474a6aa82c12840f169a04216b7");
+    }
+}
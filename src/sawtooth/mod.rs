@@ -1,6 +1,8 @@
 pub mod communication;
 pub mod factory;
 pub mod helper;
+pub mod transport;
+pub mod verification;
 
 pub use communication::Client;
 
@@ -14,6 +16,18 @@ pub trait TransactionFactory {
     fn create_transaction_for(&self, message: &TransactionPayload, header: &TransactionHeader) -> Result<Transaction, Error>;
 
     fn create_transaction_header_for(&self, message: &TransactionPayload) -> Result<TransactionHeader, Error>;
+
+    fn create_transaction_header_with(&self, message: &TransactionPayload, options: &TransactionOptions)
+                                      -> Result<TransactionHeader, Error>;
+}
+
+/// Addresses and dependencies a transaction declares in addition to the state address of its payload.
+#[derive(Debug, Default, Clone)]
+pub struct TransactionOptions {
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    pub dependencies: Vec<String>,
+    pub idempotency_key: Option<String>
 }
 
 pub trait BatchFactory {
@@ -22,6 +36,7 @@ pub trait BatchFactory {
     fn create_batch_header_for(&self, transactions: &Vec<Transaction>) -> Result<BatchHeader, Error>;
 }
 
+#[derive(Debug)]
 pub enum Error {
     RequestError,
     ResponseError,
@@ -33,5 +48,13 @@ pub enum Error {
     SerializationError(String),
     DeserializationError,
     SigningError(String),
-    KeyError(String)
+    KeyError(String),
+    UnsupportedUrl(String),
+    UnsupportedRequest(String),
+    StorageError(String),
+    VerificationError(String),
+    StateUnavailable(String),
+    ReceiptsUnavailable(String),
+    NetworkStatusUnavailable(String),
+    BlocksUnavailable(String)
 }
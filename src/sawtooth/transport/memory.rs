@@ -0,0 +1,481 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use sawtooth_sdk::messages::validator::{self, Message_MessageType};
+use sawtooth_sdk::messages::batch::{Batch, BatchList};
+use sawtooth_sdk::messages::block::{Block, BlockHeader};
+use sawtooth_sdk::messages::transaction::{Transaction, TransactionHeader};
+use sawtooth_sdk::messages::client_state::{ClientStateListRequest, ClientStateListResponse,
+                                           ClientStateListResponse_Entry, ClientStateListResponse_Status,
+                                           ClientStateGetRequest, ClientStateGetResponse, ClientStateGetResponse_Status};
+use sawtooth_sdk::messages::client_batch_submit::{ClientBatchSubmitRequest, ClientBatchSubmitResponse,
+                                                  ClientBatchSubmitResponse_Status};
+use sawtooth_sdk::messages::client_transaction::{ClientTransactionListResponse, ClientTransactionListResponse_Status};
+use sawtooth_sdk::messages::client_batch::{ClientBatchListResponse, ClientBatchListResponse_Status};
+use sawtooth_sdk::messages::client_receipt::{ClientReceiptGetRequest, ClientReceiptGetResponse, ClientReceiptGetResponse_Status};
+use sawtooth_sdk::messages::transaction_receipt::{TransactionReceipt, StateChange, StateChange_Type};
+use sawtooth_sdk::messages::client_block::{ClientBlockListRequest, ClientBlockListResponse, ClientBlockListResponse_Status,
+                                           ClientBlockGetByIdRequest, ClientBlockGetByNumRequest, ClientBlockGetResponse,
+                                           ClientBlockGetResponse_Status};
+use sawtooth_sdk::messages::client_list_control::ClientPagingResponse;
+use protobuf::{Message, ProtobufEnum};
+use sawtooth_alica_payload::{payloads, TransactionFamily};
+use crate::sawtooth::Error::{DeserializationError, SerializationError, UnsupportedRequest, StorageError};
+use crate::sawtooth::{Error, helper, verification};
+use crate::sawtooth::transport::{self, Transport};
+
+/// Serves client requests from a local ledger instead of a Sawtooth network.
+pub struct SimulatedLedger<'a> {
+    transaction_family: &'a TransactionFamily,
+    payload_format: &'a dyn payloads::Format,
+    file: Option<PathBuf>,
+    batches: RefCell<Vec<Batch>>,
+    transactions: RefCell<Vec<Transaction>>,
+    state: RefCell<BTreeMap<String, Vec<u8>>>,
+    receipts: RefCell<BTreeMap<String, TransactionReceipt>>,
+    blocks: RefCell<Vec<Block>>,
+    /// State after every block by the state root of the block.
+    snapshots: RefCell<BTreeMap<String, BTreeMap<String, Vec<u8>>>>
+}
+
+impl<'a> SimulatedLedger<'a> {
+    pub fn new(transaction_family: &'a TransactionFamily, payload_format: &'a dyn payloads::Format) -> Self {
+        SimulatedLedger {
+            transaction_family,
+            payload_format,
+            file: None,
+            batches: RefCell::new(Vec::new()),
+            transactions: RefCell::new(Vec::new()),
+            state: RefCell::new(BTreeMap::new()),
+            receipts: RefCell::new(BTreeMap::new()),
+            blocks: RefCell::new(Vec::new()),
+            snapshots: RefCell::new(BTreeMap::new())
+        }
+    }
+
+    pub fn with_file(transaction_family: &'a TransactionFamily, payload_format: &'a dyn payloads::Format, path: &Path)
+                     -> Result<Self, Error> {
+        let mut ledger = SimulatedLedger::new(transaction_family, payload_format);
+
+        if path.exists() {
+            let contents = fs::read(path).map_err(|_| StorageError(path.display().to_string()))?;
+            let batch_list = protobuf::parse_from_bytes::<BatchList>(&contents).map_err(|_| DeserializationError)?;
+            let mut known_transaction_ids = Vec::new();
+            for batch in batch_list.get_batches() {
+                match ledger.validate(batch, &mut known_transaction_ids) {
+                    Some(changes) => ledger.apply(batch, changes),
+                    None => return Err(StorageError(path.display().to_string()))
+                }
+            }
+        }
+
+        ledger.file = Some(path.to_path_buf());
+        Ok(ledger)
+    }
+
+    fn list_state_entries(&self, content: &[u8]) -> Result<validator::Message, Error> {
+        let request = protobuf::parse_from_bytes::<ClientStateListRequest>(content)
+            .map_err(|_| DeserializationError)?;
+
+        let mut response = ClientStateListResponse::new();
+        let snapshots = self.snapshots.borrow();
+        let current_state = self.state.borrow();
+        let state = match request.get_state_root() {
+            "" => &*current_state,
+            state_root => match snapshots.get(state_root) {
+                Some(snapshot) => snapshot,
+                None => {
+                    response.set_status(ClientStateListResponse_Status::INVALID_ROOT);
+                    return transport::response_message(Message_MessageType::CLIENT_STATE_LIST_RESPONSE, &response);
+                }
+            }
+        };
+
+        let start = request.get_paging().get_start();
+        let limit = match request.get_paging().get_limit() {
+            limit if limit > 0 => limit as usize,
+            _ => 100
+        };
+        let mut matching_entries = state.iter()
+            .filter(|(address, _)| address.starts_with(request.get_address()) && address.as_str() >= start);
+        let entries = matching_entries.by_ref()
+            .take(limit)
+            .map(|(address, data)| {
+                let mut entry = ClientStateListResponse_Entry::new();
+                entry.set_address(address.clone());
+                entry.set_data(data.clone());
+                entry
+            })
+            .collect();
+        let mut paging = ClientPagingResponse::new();
+        if let Some((next_address, _)) = matching_entries.next() {
+            paging.set_next(next_address.clone());
+        }
+
+        response.set_status(ClientStateListResponse_Status::OK);
+        response.set_entries(protobuf::RepeatedField::from_vec(entries));
+        response.set_paging(paging);
+        transport::response_message(Message_MessageType::CLIENT_STATE_LIST_RESPONSE, &response)
+    }
+
+    fn get_state(&self, content: &[u8]) -> Result<validator::Message, Error> {
+        let request = protobuf::parse_from_bytes::<ClientStateGetRequest>(content)
+            .map_err(|_| DeserializationError)?;
+
+        let mut response = ClientStateGetResponse::new();
+        let snapshots = self.snapshots.borrow();
+        let current_state = self.state.borrow();
+        let state = match request.get_state_root() {
+            "" => &*current_state,
+            state_root => match snapshots.get(state_root) {
+                Some(snapshot) => snapshot,
+                None => {
+                    response.set_status(ClientStateGetResponse_Status::INVALID_ROOT);
+                    return transport::response_message(Message_MessageType::CLIENT_STATE_GET_RESPONSE, &response);
+                }
+            }
+        };
+        match state.get(request.get_address()) {
+            Some(data) => {
+                response.set_status(ClientStateGetResponse_Status::OK);
+                response.set_value(data.clone());
+            },
+            None => response.set_status(ClientStateGetResponse_Status::NO_RESOURCE)
+        }
+        transport::response_message(Message_MessageType::CLIENT_STATE_GET_RESPONSE, &response)
+    }
+
+    fn submit_batches(&self, content: &[u8]) -> Result<validator::Message, Error> {
+        let request = protobuf::parse_from_bytes::<ClientBatchSubmitRequest>(content)
+            .map_err(|_| DeserializationError)?;
+
+        // Like a validator, nothing of a submission is applied if one of its batches is invalid.
+        let mut known_transaction_ids: Vec<String> = self.transactions.borrow().iter()
+            .map(|transaction| transaction.get_header_signature().to_string())
+            .collect();
+        let validated_batches: Option<Vec<(&Batch, Vec<(String, Vec<u8>)>)>> = request.get_batches().iter()
+            .map(|batch| self.validate(batch, &mut known_transaction_ids).map(|changes| (batch, changes)))
+            .collect();
+        let status = match validated_batches {
+            Some(validated_batches) => {
+                for (batch, changes) in validated_batches {
+                    self.apply(batch, changes);
+                }
+                self.persist()?;
+                ClientBatchSubmitResponse_Status::OK
+            },
+            None => ClientBatchSubmitResponse_Status::INVALID_BATCH
+        };
+
+        let mut response = ClientBatchSubmitResponse::new();
+        response.set_status(status);
+        transport::response_message(Message_MessageType::CLIENT_BATCH_SUBMIT_RESPONSE, &response)
+    }
+
+    fn list_transactions(&self) -> Result<validator::Message, Error> {
+        let mut response = ClientTransactionListResponse::new();
+        response.set_status(ClientTransactionListResponse_Status::OK);
+        response.set_transactions(protobuf::RepeatedField::from_vec(self.transactions.borrow().clone()));
+        transport::response_message(Message_MessageType::CLIENT_TRANSACTION_LIST_RESPONSE, &response)
+    }
+
+    fn list_batches(&self) -> Result<validator::Message, Error> {
+        let mut response = ClientBatchListResponse::new();
+        response.set_status(ClientBatchListResponse_Status::OK);
+        response.set_batches(protobuf::RepeatedField::from_vec(self.batches.borrow().clone()));
+        transport::response_message(Message_MessageType::CLIENT_BATCH_LIST_RESPONSE, &response)
+    }
+
+    fn get_receipts(&self, content: &[u8]) -> Result<validator::Message, Error> {
+        let request = protobuf::parse_from_bytes::<ClientReceiptGetRequest>(content)
+            .map_err(|_| DeserializationError)?;
+
+        let receipts = self.receipts.borrow();
+        let requested_receipts: Option<Vec<TransactionReceipt>> = request.get_transaction_ids().iter()
+            .map(|transaction_id| receipts.get(transaction_id).cloned())
+            .collect();
+
+        let mut response = ClientReceiptGetResponse::new();
+        match requested_receipts {
+            Some(requested_receipts) => {
+                response.set_status(ClientReceiptGetResponse_Status::OK);
+                response.set_receipts(protobuf::RepeatedField::from_vec(requested_receipts));
+            },
+            None => response.set_status(ClientReceiptGetResponse_Status::NO_RESOURCE)
+        }
+        transport::response_message(Message_MessageType::CLIENT_RECEIPT_GET_RESPONSE, &response)
+    }
+
+    /// Lists the blocks from the one the paging starts at, or the chain head, down to the genesis block.
+    fn list_blocks(&self, content: &[u8]) -> Result<validator::Message, Error> {
+        let request = protobuf::parse_from_bytes::<ClientBlockListRequest>(content)
+            .map_err(|_| DeserializationError)?;
+        let blocks = self.blocks.borrow();
+        let start = request.get_paging().get_start();
+        let limit = match request.get_paging().get_limit() {
+            limit if limit > 0 => limit as usize,
+            _ => 100
+        };
+
+        let mut response = ClientBlockListResponse::new();
+        let newest = match start {
+            "" => Some(blocks.len()),
+            start => blocks.iter().position(|block| block.get_header_signature() == start).map(|index| index + 1)
+        };
+        match newest {
+            Some(_) if blocks.is_empty() => response.set_status(ClientBlockListResponse_Status::NO_ROOT),
+            Some(newest) => {
+                let oldest = newest.saturating_sub(limit);
+                let mut paging = ClientPagingResponse::new();
+                if oldest > 0 {
+                    paging.set_next(blocks[oldest - 1].get_header_signature().to_string());
+                }
+                response.set_status(ClientBlockListResponse_Status::OK);
+                response.set_head_id(blocks[blocks.len() - 1].get_header_signature().to_string());
+                response.set_blocks(protobuf::RepeatedField::from_vec(blocks[oldest..newest].iter().rev().cloned().collect()));
+                response.set_paging(paging);
+            },
+            None => response.set_status(ClientBlockListResponse_Status::INVALID_PAGING)
+        }
+        transport::response_message(Message_MessageType::CLIENT_BLOCK_LIST_RESPONSE, &response)
+    }
+
+    fn apply(&self, batch: &Batch, changes: Vec<(String, Vec<u8>)>) {
+        let mut state = self.state.borrow_mut();
+        let mut receipts = self.receipts.borrow_mut();
+        for (transaction, (address, data)) in batch.get_transactions().iter().zip(changes) {
+            receipts.insert(transaction.get_header_signature().to_string(), receipt_of(transaction, &address, &data));
+            state.insert(address, data);
+        }
+        self.transactions.borrow_mut().extend(batch.get_transactions().iter().cloned());
+        self.batches.borrow_mut().push(batch.clone());
+        self.commit_block(batch, &state);
+    }
+
+    fn get_block(&self, request_type: Message_MessageType, content: &[u8]) -> Result<validator::Message, Error> {
+        let blocks = self.blocks.borrow();
+        let block = if request_type == Message_MessageType::CLIENT_BLOCK_GET_BY_NUM_REQUEST {
+            let request = protobuf::parse_from_bytes::<ClientBlockGetByNumRequest>(content)
+                .map_err(|_| DeserializationError)?;
+            blocks.get(request.get_block_num() as usize)
+        } else {
+            let request = protobuf::parse_from_bytes::<ClientBlockGetByIdRequest>(content)
+                .map_err(|_| DeserializationError)?;
+            blocks.iter().find(|block| block.get_header_signature() == request.get_block_id())
+        };
+
+        let mut response = ClientBlockGetResponse::new();
+        match block {
+            Some(block) => {
+                response.set_status(ClientBlockGetResponse_Status::OK);
+                response.set_block(block.clone());
+            },
+            None => response.set_status(ClientBlockGetResponse_Status::NO_RESOURCE)
+        }
+        transport::response_message(Message_MessageType::CLIENT_BLOCK_GET_RESPONSE, &response)
+    }
+
+    fn commit_block(&self, batch: &Batch, state: &BTreeMap<String, Vec<u8>>) {
+        let mut blocks = self.blocks.borrow_mut();
+        let previous_block_id = blocks.last()
+            .map_or_else(|| "0000000000000000".to_string(), |block| block.get_header_signature().to_string());
+
+        let mut header = BlockHeader::new();
+        header.set_block_num(blocks.len() as u64);
+        header.set_batch_ids(protobuf::RepeatedField::from_vec(vec![batch.get_header_signature().to_string()]));
+        header.set_previous_block_id(previous_block_id.clone());
+        header.set_state_root_hash(state_root_of(state));
+        self.snapshots.borrow_mut().insert(header.get_state_root_hash().to_string(), state.clone());
+
+        let mut block = Block::new();
+        block.set_header(header.write_to_bytes().unwrap_or_default());
+        block.set_header_signature(helper::calculate_checksum(&format!("{}{}", previous_block_id, batch.get_header_signature())));
+        block.set_batches(protobuf::RepeatedField::from_vec(vec![batch.clone()]));
+        blocks.push(block);
+    }
+
+    /// Adds the IDs of the transactions of a valid batch to the known ones.
+    fn validate(&self, batch: &Batch, known_transaction_ids: &mut Vec<String>) -> Option<Vec<(String, Vec<u8>)>> {
+        if !verification::verify_batch(batch).is_empty() {
+            return None;
+        }
+
+        let mut changes = Vec::new();
+        let mut batch_transaction_ids = known_transaction_ids.clone();
+        for transaction in batch.get_transactions() {
+            changes.push(self.validate_transaction(transaction, &batch_transaction_ids)?);
+            batch_transaction_ids.push(transaction.get_header_signature().to_string());
+        }
+        *known_transaction_ids = batch_transaction_ids;
+        Some(changes)
+    }
+
+    fn validate_transaction(&self, transaction: &Transaction, known_transaction_ids: &[String])
+                            -> Option<(String, Vec<u8>)> {
+        let header = protobuf::parse_from_bytes::<TransactionHeader>(transaction.get_header()).ok()?;
+        if header.get_family_name() != self.transaction_family.name
+            || known_transaction_ids.iter().any(|id| id == transaction.get_header_signature())
+            || !header.get_dependencies().iter().all(|dependency| known_transaction_ids.contains(dependency)) {
+            return None;
+        }
+
+        let payload = self.payload_format.deserialize(transaction.get_payload()).ok()?;
+        let address = self.transaction_family.calculate_state_address_for(&payload);
+        if !header.get_outputs().contains(&address) {
+            return None;
+        }
+
+        Some((address, transaction.get_payload().to_vec()))
+    }
+
+    fn persist(&self) -> Result<(), Error> {
+        if let Some(path) = &self.file {
+            let mut batch_list = BatchList::new();
+            batch_list.set_batches(protobuf::RepeatedField::from_vec(self.batches.borrow().clone()));
+            let contents = batch_list.write_to_bytes().map_err(|_| SerializationError("Batch List".to_string()))?;
+            fs::write(path, contents).map_err(|_| StorageError(path.display().to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Transport for SimulatedLedger<'a> {
+    fn send(&self, request_type: Message_MessageType, content: &[u8]) -> Result<validator::Message, Error> {
+        match request_type {
+            Message_MessageType::CLIENT_STATE_LIST_REQUEST => self.list_state_entries(content),
+            Message_MessageType::CLIENT_STATE_GET_REQUEST => self.get_state(content),
+            Message_MessageType::CLIENT_BATCH_SUBMIT_REQUEST => self.submit_batches(content),
+            Message_MessageType::CLIENT_TRANSACTION_LIST_REQUEST => self.list_transactions(),
+            Message_MessageType::CLIENT_BATCH_LIST_REQUEST => self.list_batches(),
+            Message_MessageType::CLIENT_RECEIPT_GET_REQUEST => self.get_receipts(content),
+            Message_MessageType::CLIENT_BLOCK_LIST_REQUEST => self.list_blocks(content),
+            Message_MessageType::CLIENT_BLOCK_GET_BY_ID_REQUEST | Message_MessageType::CLIENT_BLOCK_GET_BY_NUM_REQUEST =>
+                self.get_block(request_type, content),
+            unsupported => Err(UnsupportedRequest(unsupported.descriptor().name().to_string()))
+        }
+    }
+}
+
+fn state_root_of(state: &BTreeMap<String, Vec<u8>>) -> String {
+    let mut contents = Vec::new();
+    for (address, data) in state {
+        contents.extend_from_slice(address.as_bytes());
+        contents.extend_from_slice(data);
+    }
+    helper::calculate_checksum(&contents)
+}
+
+fn receipt_of(transaction: &Transaction, address: &str, data: &[u8]) -> TransactionReceipt {
+    let mut state_change = StateChange::new();
+    state_change.set_address(address.to_string());
+    state_change.set_value(data.to_vec());
+    state_change.set_field_type(StateChange_Type::SET);
+
+    let mut receipt = TransactionReceipt::new();
+    receipt.set_transaction_id(transaction.get_header_signature().to_string());
+    receipt.set_state_changes(protobuf::RepeatedField::from_vec(vec![state_change]));
+    receipt
+}
+
+#[cfg(test)]
+mod test {
+    use sawtooth_sdk::signing;
+    use sawtooth_sdk::messages::validator::Message_MessageType;
+    use sawtooth_sdk::messages::client_batch_submit::{ClientBatchSubmitRequest, ClientBatchSubmitResponse,
+                                                      ClientBatchSubmitResponse_Status};
+    use sawtooth_sdk::messages::client_state::{ClientStateListRequest, ClientStateListResponse};
+    use sawtooth_alica_payload::{payloads, TransactionFamily};
+    use protobuf::Message;
+    use crate::sawtooth::{BatchFactory, Client, TransactionFactory};
+    use crate::sawtooth::factory::GeneralPurposeComponentFactory;
+    use crate::sawtooth::transport::Transport;
+    use crate::sawtooth::transport::memory::SimulatedLedger;
+
+    fn create_test_signer<'a>() -> signing::Signer<'a> {
+        let context = signing::create_context("secp256k1").expect("No secp256k1 context");
+        let private_key = context.new_random_private_key().expect("Could not create private key");
+        signing::Signer::new_boxed(context, private_key)
+    }
+
+    #[test]
+    fn it_stores_submitted_payloads_at_their_state_address() {
+        let family = TransactionFamily::new("alica_messages", &vec!["0.1.0".to_string()]);
+        let format = payloads::pipe_separated::Format::default();
+        let factory = GeneralPurposeComponentFactory::new(&family, &format, create_test_signer());
+        let client = Client::with_transport(Box::new(SimulatedLedger::new(&family, &format)), &factory);
+        let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000);
+
+        client.create_batch(&[&payload]).expect("Batch was rejected");
+        let entries = client.list_state_entries().expect("Could not list state");
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].get_address(), family.calculate_state_address_for(&payload));
+        assert_eq!(client.list_transactions().expect("Could not list transactions").len(), 1);
+    }
+
+    #[test]
+    fn it_rejects_batches_signed_for_another_family() {
+        let family = TransactionFamily::new("alica_messages", &vec!["0.1.0".to_string()]);
+        let other_family = TransactionFamily::new("other_messages", &vec!["0.1.0".to_string()]);
+        let format = payloads::pipe_separated::Format::default();
+        let factory = GeneralPurposeComponentFactory::new(&other_family, &format, create_test_signer());
+        let client = Client::with_transport(Box::new(SimulatedLedger::new(&family, &format)), &factory);
+        let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000);
+
+        let result = client.create_batch(&[&payload]);
+
+        assert!(result.is_err());
+        assert!(client.list_state_entries().expect("Could not list state").is_empty());
+    }
+
+    #[test]
+    fn it_applies_no_batch_of_a_submission_with_an_invalid_batch() {
+        let family = TransactionFamily::new("alica_messages", &vec!["0.1.0".to_string()]);
+        let format = payloads::pipe_separated::Format::default();
+        let factory = GeneralPurposeComponentFactory::new(&family, &format, create_test_signer());
+        let ledger = SimulatedLedger::new(&family, &format);
+        let batches: Vec<_> = ["first", "second"].iter()
+            .map(|message| {
+                let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", message.as_bytes(), 1605000000);
+                let header = factory.create_transaction_header_for(&payload).expect("Could not create header");
+                let transactions = vec![factory.create_transaction_for(&payload, &header).expect("Could not create transaction")];
+                let batch_header = factory.create_batch_header_for(&transactions).expect("Could not create batch header");
+                factory.create_batch_for(&transactions, &batch_header).expect("Could not create batch")
+            })
+            .collect();
+        let mut invalid_batch = batches[1].clone();
+        invalid_batch.mut_transactions()[0].set_payload("agent1|AlicaEngineInfo|altered|1605000000".as_bytes().to_vec());
+        let mut request = ClientBatchSubmitRequest::new();
+        request.set_batches(protobuf::RepeatedField::from_vec(vec![batches[0].clone(), invalid_batch]));
+
+        let response = ledger.send(Message_MessageType::CLIENT_BATCH_SUBMIT_REQUEST, &request.write_to_bytes().unwrap())
+            .expect("Request failed");
+        let status = protobuf::parse_from_bytes::<ClientBatchSubmitResponse>(response.get_content()).unwrap().get_status();
+
+        assert_eq!(status, ClientBatchSubmitResponse_Status::INVALID_BATCH);
+        assert!(ledger.transactions.borrow().is_empty());
+        assert!(ledger.state.borrow().is_empty());
+    }
+
+    #[test]
+    fn it_lists_state_in_pages_the_client_follows() {
+        let family = TransactionFamily::new("alica_messages", &vec!["0.1.0".to_string()]);
+        let format = payloads::pipe_separated::Format::default();
+        let factory = GeneralPurposeComponentFactory::new(&family, &format, create_test_signer());
+        let ledger = SimulatedLedger::new(&family, &format);
+        let payloads: Vec<payloads::TransactionPayload> = (0..150)
+            .map(|timestamp| payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), timestamp))
+            .collect();
+        let client = Client::with_transport(Box::new(ledger), &factory);
+        client.create_batch(&payloads.iter().collect::<Vec<_>>()).expect("Batch was rejected");
+
+        let response = client.send(&ClientStateListRequest::new(), Message_MessageType::CLIENT_STATE_LIST_REQUEST)
+            .expect("Request failed");
+        let first_page = protobuf::parse_from_bytes::<ClientStateListResponse>(response.get_content()).unwrap();
+
+        assert_eq!(first_page.get_entries().len(), 100);
+        assert!(!first_page.get_paging().get_next().is_empty());
+        assert_eq!(client.list_state_entries().expect("Could not list state").len(), 150);
+    }
+}
@@ -0,0 +1,246 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use sawtooth_sdk::messages::validator::{self, Message_MessageType};
+use sawtooth_sdk::messages::transaction::{Transaction, TransactionList};
+use sawtooth_sdk::messages::transaction_receipt::{StateChange, StateChangeList, StateChange_Type};
+use sawtooth_sdk::messages::client_state::{ClientStateListRequest, ClientStateListResponse,
+                                           ClientStateListResponse_Entry, ClientStateListResponse_Status,
+                                           ClientStateGetRequest, ClientStateGetResponse, ClientStateGetResponse_Status};
+use sawtooth_sdk::messages::client_transaction::{ClientTransactionListResponse, ClientTransactionListResponse_Status};
+use protobuf::{Message, ProtobufEnum};
+use crate::sawtooth::Error::{DeserializationError, SerializationError, UnsupportedRequest, StorageError};
+use crate::sawtooth::Error;
+use crate::sawtooth::transport::{self, Transport};
+
+const HEAD_FILE_NAME: &str = "head";
+const TRANSACTIONS_FILE_NAME: &str = "transactions";
+const STATE_FILE_NAME: &str = "state";
+
+/// Block a mirror was last synced to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MirroredBlock {
+    pub block_num: u64,
+    pub block_id: String
+}
+
+/// Serves read requests from the transactions and state changes the sync command appended.
+pub struct Mirror {
+    path: PathBuf,
+    /// Last synced block and the file lengths that belong to it, later bytes are from an interrupted sync.
+    head: RefCell<Option<(MirroredBlock, u64, u64)>>,
+    transactions: RefCell<Vec<Transaction>>,
+    state: RefCell<BTreeMap<String, Vec<u8>>>
+}
+
+impl Mirror {
+    /// Opens the mirror in the directory, which is empty if nothing was synced to it yet.
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        let mirror = Mirror {
+            path: path.to_path_buf(),
+            head: RefCell::new(None),
+            transactions: RefCell::new(Vec::new()),
+            state: RefCell::new(BTreeMap::new())
+        };
+
+        let head_file = path.join(HEAD_FILE_NAME);
+        if !head_file.exists() {
+            return Ok(mirror);
+        }
+        let head = fs::read_to_string(&head_file).map_err(|_| mirror.storage_error(HEAD_FILE_NAME))?;
+        let (block, transactions_length, state_length) = parse_head(&head)
+            .ok_or_else(|| mirror.storage_error(HEAD_FILE_NAME))?;
+
+        let transactions = mirror.read(TRANSACTIONS_FILE_NAME, transactions_length)?;
+        let transaction_list = protobuf::parse_from_bytes::<TransactionList>(&transactions)
+            .map_err(|_| DeserializationError)?;
+        let state = mirror.read(STATE_FILE_NAME, state_length)?;
+        let state_change_list = protobuf::parse_from_bytes::<StateChangeList>(&state)
+            .map_err(|_| DeserializationError)?;
+
+        mirror.transactions.borrow_mut().extend(transaction_list.get_transactions().iter().cloned());
+        mirror.apply(state_change_list.get_state_changes());
+        mirror.head.replace(Some((block, transactions_length, state_length)));
+        Ok(mirror)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the last synced block or None if nothing was synced yet.
+    pub fn head(&self) -> Option<MirroredBlock> {
+        self.head.borrow().as_ref().map(|(block, _, _)| block.clone())
+    }
+
+    /// Expects the transactions and state changes of all blocks up to this one in commit order.
+    pub fn append(&self, block: MirroredBlock, transactions: &[Transaction], state_changes: &[StateChange])
+                  -> Result<(), Error> {
+        fs::create_dir_all(&self.path).map_err(|_| StorageError(self.path.display().to_string()))?;
+        let (transactions_length, state_length) = match &*self.head.borrow() {
+            Some((_, transactions_length, state_length)) => (*transactions_length, *state_length),
+            None => (0, 0)
+        };
+
+        let mut transaction_list = TransactionList::new();
+        transaction_list.set_transactions(protobuf::RepeatedField::from_vec(transactions.to_vec()));
+        let transactions_length = self.append_to(TRANSACTIONS_FILE_NAME, transactions_length, &transaction_list
+            .write_to_bytes().map_err(|_| SerializationError("Transaction List".to_string()))?)?;
+
+        let mut state_change_list = StateChangeList::new();
+        state_change_list.set_state_changes(protobuf::RepeatedField::from_vec(state_changes.to_vec()));
+        let state_length = self.append_to(STATE_FILE_NAME, state_length, &state_change_list
+            .write_to_bytes().map_err(|_| SerializationError("State Change List".to_string()))?)?;
+
+        // The head is written last, so the appended data only counts once all of it is on disk.
+        let head = format!("{} {} {} {}\n", block.block_num, block.block_id, transactions_length, state_length);
+        fs::write(self.path.join(HEAD_FILE_NAME), head).map_err(|_| self.storage_error(HEAD_FILE_NAME))?;
+
+        self.transactions.borrow_mut().extend(transactions.iter().cloned());
+        self.apply(state_changes);
+        self.head.replace(Some((block, transactions_length, state_length)));
+        Ok(())
+    }
+
+    fn read(&self, file_name: &str, length: u64) -> Result<Vec<u8>, Error> {
+        let mut contents = fs::read(self.path.join(file_name)).map_err(|_| self.storage_error(file_name))?;
+        if (contents.len() as u64) < length {
+            return Err(self.storage_error(file_name));
+        }
+        contents.truncate(length as usize);
+        Ok(contents)
+    }
+
+    /// Appends the data after the first `length` bytes of the file and returns the new length.
+    fn append_to(&self, file_name: &str, length: u64, data: &[u8]) -> Result<u64, Error> {
+        let mut file = OpenOptions::new().create(true).write(true).truncate(false).open(self.path.join(file_name))
+            .map_err(|_| self.storage_error(file_name))?;
+        file.set_len(length)
+            .and_then(|_| file.seek(SeekFrom::Start(length)))
+            .and_then(|_| file.write_all(data))
+            .and_then(|_| file.sync_all())
+            .map_err(|_| self.storage_error(file_name))?;
+        Ok(length + data.len() as u64)
+    }
+
+    fn apply(&self, state_changes: &[StateChange]) {
+        let mut state = self.state.borrow_mut();
+        for state_change in state_changes {
+            match state_change.get_field_type() {
+                StateChange_Type::SET => state.insert(state_change.get_address().to_string(), state_change.get_value().to_vec()),
+                StateChange_Type::DELETE => state.remove(state_change.get_address()),
+                StateChange_Type::TYPE_UNSET => None
+            };
+        }
+    }
+
+    fn storage_error(&self, file_name: &str) -> Error {
+        StorageError(self.path.join(file_name).display().to_string())
+    }
+
+    fn list_state_entries(&self, content: &[u8]) -> Result<validator::Message, Error> {
+        let request = protobuf::parse_from_bytes::<ClientStateListRequest>(content)
+            .map_err(|_| DeserializationError)?;
+
+        let entries = self.state.borrow().iter()
+            .filter(|(address, _)| address.starts_with(request.get_address()))
+            .map(|(address, data)| {
+                let mut entry = ClientStateListResponse_Entry::new();
+                entry.set_address(address.clone());
+                entry.set_data(data.clone());
+                entry
+            })
+            .collect();
+
+        let mut response = ClientStateListResponse::new();
+        response.set_status(ClientStateListResponse_Status::OK);
+        response.set_entries(protobuf::RepeatedField::from_vec(entries));
+        transport::response_message(Message_MessageType::CLIENT_STATE_LIST_RESPONSE, &response)
+    }
+
+    fn get_state(&self, content: &[u8]) -> Result<validator::Message, Error> {
+        let request = protobuf::parse_from_bytes::<ClientStateGetRequest>(content)
+            .map_err(|_| DeserializationError)?;
+
+        let mut response = ClientStateGetResponse::new();
+        match self.state.borrow().get(request.get_address()) {
+            Some(data) => {
+                response.set_status(ClientStateGetResponse_Status::OK);
+                response.set_value(data.clone());
+            },
+            None => response.set_status(ClientStateGetResponse_Status::NO_RESOURCE)
+        }
+        transport::response_message(Message_MessageType::CLIENT_STATE_GET_RESPONSE, &response)
+    }
+
+    fn list_transactions(&self) -> Result<validator::Message, Error> {
+        let mut response = ClientTransactionListResponse::new();
+        response.set_status(ClientTransactionListResponse_Status::OK);
+        response.set_transactions(protobuf::RepeatedField::from_vec(self.transactions.borrow().clone()));
+        transport::response_message(Message_MessageType::CLIENT_TRANSACTION_LIST_RESPONSE, &response)
+    }
+}
+
+impl Transport for Mirror {
+    fn send(&self, request_type: Message_MessageType, content: &[u8]) -> Result<validator::Message, Error> {
+        match request_type {
+            Message_MessageType::CLIENT_STATE_LIST_REQUEST => self.list_state_entries(content),
+            Message_MessageType::CLIENT_STATE_GET_REQUEST => self.get_state(content),
+            Message_MessageType::CLIENT_TRANSACTION_LIST_REQUEST => self.list_transactions(),
+            unsupported => Err(UnsupportedRequest(unsupported.descriptor().name().to_string()))
+        }
+    }
+}
+
+fn parse_head(head: &str) -> Option<(MirroredBlock, u64, u64)> {
+    let fields: Vec<&str> = head.split_whitespace().collect();
+    match fields.as_slice() {
+        [block_num, block_id, transactions_length, state_length] => Some((
+            MirroredBlock { block_num: block_num.parse().ok()?, block_id: block_id.to_string() },
+            transactions_length.parse().ok()?,
+            state_length.parse().ok()?
+        )),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use sawtooth_sdk::messages::transaction::Transaction;
+    use sawtooth_sdk::messages::transaction_receipt::{StateChange, StateChange_Type};
+    use crate::sawtooth::transport::mirror::{Mirror, MirroredBlock};
+
+    fn state_change(address: &str, value: &str, change_type: StateChange_Type) -> StateChange {
+        let mut state_change = StateChange::new();
+        state_change.set_address(address.to_string());
+        state_change.set_value(value.as_bytes().to_vec());
+        state_change.set_field_type(change_type);
+        state_change
+    }
+
+    #[test]
+    fn it_replays_appended_state_changes_and_ignores_data_after_the_head() {
+        let path = std::env::temp_dir().join(format!("mirror-{}", uuid::Uuid::new_v4()));
+        let mirror = Mirror::open(&path).expect("Could not open mirror");
+        let mut transaction = Transaction::new();
+        transaction.set_header_signature("transaction1".to_string());
+        mirror.append(MirroredBlock { block_num: 0, block_id: "block0".to_string() }, &[transaction],
+                      &[state_change("a1", "first", StateChange_Type::SET), state_change("a2", "second", StateChange_Type::SET)])
+            .expect("Could not append");
+        mirror.append(MirroredBlock { block_num: 1, block_id: "block1".to_string() }, &[],
+                      &[state_change("a1", "", StateChange_Type::DELETE)])
+            .expect("Could not append");
+        let mut state_file = fs::OpenOptions::new().append(true).open(path.join("state")).unwrap();
+        std::io::Write::write_all(&mut state_file, b"interrupted").unwrap();
+
+        let reopened = Mirror::open(&path).expect("Could not reopen mirror");
+
+        assert_eq!(reopened.head(), Some(MirroredBlock { block_num: 1, block_id: "block1".to_string() }));
+        assert_eq!(reopened.transactions.borrow().len(), 1);
+        assert_eq!(reopened.state.borrow().keys().collect::<Vec<_>>(), vec!["a2"]);
+        fs::remove_dir_all(&path).unwrap();
+    }
+}
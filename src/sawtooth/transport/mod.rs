@@ -0,0 +1,33 @@
+pub mod zmq;
+pub mod rest;
+pub mod memory;
+pub mod mirror;
+
+use sawtooth_sdk::messages::validator::{self, Message_MessageType};
+use crate::sawtooth::Error;
+
+pub trait Transport {
+    fn send(&self, request_type: Message_MessageType, content: &[u8]) -> Result<validator::Message, Error>;
+}
+
+pub fn connect(url: &str) -> Result<Box<dyn Transport>, Error> {
+    if url.starts_with("tcp://") {
+        Ok(Box::from(zmq::ZmqTransport::new(url)))
+    } else if url.starts_with("http://") || url.starts_with("https://") {
+        Ok(Box::from(rest::RestTransport::new(url)))
+    } else {
+        Err(Error::UnsupportedUrl(url.to_string()))
+    }
+}
+
+pub fn response_message(response_type: Message_MessageType, response: &dyn protobuf::Message)
+                        -> Result<validator::Message, Error> {
+    let content = response.write_to_bytes().map_err(|_| Error::SerializationError("Response".to_string()))?;
+
+    let mut message = validator::Message::new();
+    message.set_message_type(response_type);
+    message.set_correlation_id(uuid::Uuid::new_v4().to_string());
+    message.set_content(content);
+
+    Ok(message)
+}
@@ -0,0 +1,837 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use sawtooth_sdk::messages::validator::{self, Message_MessageType};
+use sawtooth_sdk::messages::batch::{Batch, BatchHeader, BatchList};
+use sawtooth_sdk::messages::transaction::{Transaction, TransactionHeader};
+use sawtooth_sdk::messages::client_state::{ClientStateListRequest, ClientStateListResponse,
+                                           ClientStateListResponse_Entry, ClientStateListResponse_Status,
+                                           ClientStateGetRequest, ClientStateGetResponse, ClientStateGetResponse_Status};
+use sawtooth_sdk::messages::client_batch_submit::{ClientBatchSubmitRequest, ClientBatchSubmitResponse,
+                                                  ClientBatchSubmitResponse_Status};
+use sawtooth_sdk::messages::client_transaction::{ClientTransactionListResponse, ClientTransactionListResponse_Status};
+use sawtooth_sdk::messages::client_batch::{ClientBatchListResponse, ClientBatchListResponse_Status};
+use sawtooth_sdk::messages::client_receipt::{ClientReceiptGetRequest, ClientReceiptGetResponse, ClientReceiptGetResponse_Status};
+use sawtooth_sdk::messages::transaction_receipt::{TransactionReceipt, StateChange, StateChange_Type};
+use sawtooth_sdk::messages::events::{Event, Event_Attribute};
+use sawtooth_sdk::messages::client_status::{ClientStatusGetResponse, ClientStatusGetResponse_Peer, ClientStatusGetResponse_Status};
+use sawtooth_sdk::messages::client_peers::{ClientPeersGetResponse, ClientPeersGetResponse_Status};
+use sawtooth_sdk::messages::client_block::{ClientBlockListRequest, ClientBlockListResponse, ClientBlockListResponse_Status,
+                                           ClientBlockGetByIdRequest, ClientBlockGetByNumRequest, ClientBlockGetResponse,
+                                           ClientBlockGetResponse_Status};
+use sawtooth_sdk::messages::client_list_control::ClientPagingResponse;
+use sawtooth_sdk::messages::block::{Block, BlockHeader};
+use protobuf::{Message, ProtobufEnum};
+use serde_json::Value;
+use crate::sawtooth::Error::{RequestError, DeserializationError, SerializationError, UnsupportedRequest};
+use crate::sawtooth::Error;
+use crate::sawtooth::transport::{self, Transport};
+
+pub struct RestTransport {
+    base_url: String,
+    /// Block IDs by the state root of the block, the REST API reads earlier state at a head block only.
+    block_ids: RefCell<BTreeMap<String, String>>
+}
+
+impl RestTransport {
+    pub fn new(url: &str) -> Self {
+        RestTransport {
+            base_url: url.trim_end_matches('/').to_string(),
+            block_ids: RefCell::new(BTreeMap::new())
+        }
+    }
+
+    fn list_state_entries(&self, content: &[u8]) -> Result<validator::Message, Error> {
+        let request = protobuf::parse_from_bytes::<ClientStateListRequest>(content)
+            .map_err(|_| DeserializationError)?;
+        let mut query = Vec::new();
+        if !request.get_state_root().is_empty() {
+            match self.head_for(request.get_state_root())? {
+                Some(head) => query.push(format!("head={}", head)),
+                None => {
+                    let mut response = ClientStateListResponse::new();
+                    response.set_status(ClientStateListResponse_Status::INVALID_ROOT);
+                    return transport::response_message(Message_MessageType::CLIENT_STATE_LIST_RESPONSE, &response);
+                }
+            }
+        }
+        if !request.get_address().is_empty() {
+            query.push(format!("address={}", request.get_address()));
+        }
+        let url = if query.is_empty() {
+            format!("{}/state", self.base_url)
+        } else {
+            format!("{}/state?{}", self.base_url, query.join("&"))
+        };
+
+        let mut entries = Vec::new();
+        for item in self.get_all_pages(&url)? {
+            let mut entry = ClientStateListResponse_Entry::new();
+            entry.set_address(string_field(&item, "address")?);
+            entry.set_data(base64_field(&item, "data")?);
+            entries.push(entry);
+        }
+
+        let mut response = ClientStateListResponse::new();
+        response.set_status(ClientStateListResponse_Status::OK);
+        response.set_entries(protobuf::RepeatedField::from_vec(entries));
+        transport::response_message(Message_MessageType::CLIENT_STATE_LIST_RESPONSE, &response)
+    }
+
+    fn get_state(&self, content: &[u8]) -> Result<validator::Message, Error> {
+        let request = protobuf::parse_from_bytes::<ClientStateGetRequest>(content)
+            .map_err(|_| DeserializationError)?;
+        let mut url = format!("{}/state/{}", self.base_url, request.get_address());
+        if !request.get_state_root().is_empty() {
+            match self.head_for(request.get_state_root())? {
+                Some(head) => url.push_str(&format!("?head={}", head)),
+                None => {
+                    let mut response = ClientStateGetResponse::new();
+                    response.set_status(ClientStateGetResponse_Status::INVALID_ROOT);
+                    return transport::response_message(Message_MessageType::CLIENT_STATE_GET_RESPONSE, &response);
+                }
+            }
+        }
+
+        let http_response = ureq::get(&url).call();
+        if http_response.synthetic() {
+            return Err(RequestError);
+        }
+
+        let mut response = ClientStateGetResponse::new();
+        match http_response.status() {
+            200..=299 => {
+                let body: Value = http_response.into_json().map_err(|_| DeserializationError)?;
+                response.set_status(ClientStateGetResponse_Status::OK);
+                response.set_value(base64_field(&body, "data")?);
+            },
+            404 => response.set_status(ClientStateGetResponse_Status::NO_RESOURCE),
+            400 => response.set_status(ClientStateGetResponse_Status::INVALID_ADDRESS),
+            503 => response.set_status(ClientStateGetResponse_Status::NOT_READY),
+            _ => response.set_status(ClientStateGetResponse_Status::INTERNAL_ERROR)
+        }
+        transport::response_message(Message_MessageType::CLIENT_STATE_GET_RESPONSE, &response)
+    }
+
+    fn submit_batches(&self, content: &[u8]) -> Result<validator::Message, Error> {
+        let request = protobuf::parse_from_bytes::<ClientBatchSubmitRequest>(content)
+            .map_err(|_| DeserializationError)?;
+        let mut batch_list = BatchList::new();
+        batch_list.set_batches(protobuf::RepeatedField::from_vec(request.get_batches().to_vec()));
+        let body = batch_list.write_to_bytes().map_err(|_| SerializationError("Batch List".to_string()))?;
+
+        let http_response = ureq::post(&format!("{}/batches", self.base_url))
+            .set("Content-Type", "application/octet-stream")
+            .send_bytes(&body);
+        if http_response.synthetic() {
+            return Err(RequestError);
+        }
+
+        let status = match http_response.status() {
+            200..=299 => ClientBatchSubmitResponse_Status::OK,
+            400 => ClientBatchSubmitResponse_Status::INVALID_BATCH,
+            429 => ClientBatchSubmitResponse_Status::QUEUE_FULL,
+            _ => ClientBatchSubmitResponse_Status::INTERNAL_ERROR
+        };
+
+        let mut response = ClientBatchSubmitResponse::new();
+        response.set_status(status);
+        transport::response_message(Message_MessageType::CLIENT_BATCH_SUBMIT_RESPONSE, &response)
+    }
+
+    fn list_transactions(&self) -> Result<validator::Message, Error> {
+        let url = format!("{}/transactions", self.base_url);
+
+        let transactions = self.get_all_pages(&url)?.iter()
+            .map(transaction_from)
+            .collect::<Result<Vec<Transaction>, Error>>()?;
+
+        let mut response = ClientTransactionListResponse::new();
+        response.set_status(ClientTransactionListResponse_Status::OK);
+        response.set_transactions(protobuf::RepeatedField::from_vec(transactions));
+        transport::response_message(Message_MessageType::CLIENT_TRANSACTION_LIST_RESPONSE, &response)
+    }
+
+    fn list_batches(&self) -> Result<validator::Message, Error> {
+        let url = format!("{}/batches", self.base_url);
+
+        let batches = self.get_all_pages(&url)?.iter()
+            .map(batch_from)
+            .collect::<Result<Vec<Batch>, Error>>()?;
+
+        let mut response = ClientBatchListResponse::new();
+        response.set_status(ClientBatchListResponse_Status::OK);
+        response.set_batches(protobuf::RepeatedField::from_vec(batches));
+        transport::response_message(Message_MessageType::CLIENT_BATCH_LIST_RESPONSE, &response)
+    }
+
+    fn get_receipts(&self, content: &[u8]) -> Result<validator::Message, Error> {
+        let request = protobuf::parse_from_bytes::<ClientReceiptGetRequest>(content)
+            .map_err(|_| DeserializationError)?;
+
+        let url = format!("{}/receipts?id={}", self.base_url, request.get_transaction_ids().join(","));
+        let http_response = ureq::get(&url).call();
+        if http_response.synthetic() {
+            return Err(RequestError);
+        }
+
+        let mut response = ClientReceiptGetResponse::new();
+        match http_response.status() {
+            200..=299 => {
+                let body: Value = http_response.into_json().map_err(|_| DeserializationError)?;
+                let receipts = match body.get("data") {
+                    Some(Value::Array(receipts)) => receipts.iter()
+                        .map(receipt_from)
+                        .collect::<Result<Vec<TransactionReceipt>, Error>>()?,
+                    _ => return Err(DeserializationError)
+                };
+                response.set_status(ClientReceiptGetResponse_Status::OK);
+                response.set_receipts(protobuf::RepeatedField::from_vec(receipts));
+            },
+            404 => response.set_status(ClientReceiptGetResponse_Status::NO_RESOURCE),
+            400 => response.set_status(ClientReceiptGetResponse_Status::INVALID_ID),
+            _ => response.set_status(ClientReceiptGetResponse_Status::INTERNAL_ERROR)
+        }
+        transport::response_message(Message_MessageType::CLIENT_RECEIPT_GET_RESPONSE, &response)
+    }
+
+    fn get_status(&self) -> Result<validator::Message, Error> {
+        let body = self.get_json(&format!("{}/status", self.base_url))?;
+        let status = body.get("data").ok_or(DeserializationError)?;
+
+        let mut peers = Vec::new();
+        for item in list_field(status, "peers")? {
+            let mut peer = ClientStatusGetResponse_Peer::new();
+            peer.set_endpoint(string_field(item, "endpoint")?);
+            peers.push(peer);
+        }
+
+        let mut response = ClientStatusGetResponse::new();
+        response.set_status(ClientStatusGetResponse_Status::OK);
+        response.set_endpoint(string_field(status, "endpoint")?);
+        response.set_peers(protobuf::RepeatedField::from_vec(peers));
+        transport::response_message(Message_MessageType::CLIENT_STATUS_GET_RESPONSE, &response)
+    }
+
+    fn get_peers(&self) -> Result<validator::Message, Error> {
+        let body = self.get_json(&format!("{}/peers", self.base_url))?;
+
+        let mut response = ClientPeersGetResponse::new();
+        response.set_status(ClientPeersGetResponse_Status::OK);
+        response.set_peers(protobuf::RepeatedField::from_vec(string_list_field(&body, "data")?));
+        transport::response_message(Message_MessageType::CLIENT_PEERS_GET_RESPONSE, &response)
+    }
+
+    /// Lists a single page of blocks, the position of the next page is passed on as is.
+    fn list_blocks(&self, content: &[u8]) -> Result<validator::Message, Error> {
+        let request = protobuf::parse_from_bytes::<ClientBlockListRequest>(content)
+            .map_err(|_| DeserializationError)?;
+        let mut url = match request.get_paging().get_limit() {
+            0 => format!("{}/blocks", self.base_url),
+            limit => format!("{}/blocks?limit={}", self.base_url, limit)
+        };
+        if !request.get_paging().get_start().is_empty() {
+            url.push_str(if url.contains('?') { "&" } else { "?" });
+            url.push_str(&format!("start={}", request.get_paging().get_start()));
+        }
+
+        let http_response = ureq::get(&url).call();
+        if http_response.synthetic() {
+            return Err(RequestError);
+        }
+
+        let mut response = ClientBlockListResponse::new();
+        match http_response.status() {
+            200..=299 => {
+                let body: Value = http_response.into_json().map_err(|_| DeserializationError)?;
+                let blocks = list_field(&body, "data")?.iter()
+                    .map(block_from)
+                    .collect::<Result<Vec<Block>, Error>>()?;
+                self.remember(&blocks);
+                let mut paging = ClientPagingResponse::new();
+                if let Some(next_position) = body.get("paging").and_then(|paging| paging.get("next_position")) {
+                    paging.set_next(next_position.as_str().ok_or(DeserializationError)?.to_string());
+                }
+                response.set_status(ClientBlockListResponse_Status::OK);
+                response.set_head_id(string_field(&body, "head").unwrap_or_default());
+                response.set_blocks(protobuf::RepeatedField::from_vec(blocks));
+                response.set_paging(paging);
+            },
+            400 => response.set_status(ClientBlockListResponse_Status::INVALID_PAGING),
+            404 => response.set_status(ClientBlockListResponse_Status::NO_RESOURCE),
+            503 => response.set_status(ClientBlockListResponse_Status::NOT_READY),
+            _ => response.set_status(ClientBlockListResponse_Status::INTERNAL_ERROR)
+        }
+        transport::response_message(Message_MessageType::CLIENT_BLOCK_LIST_RESPONSE, &response)
+    }
+
+    /// Gets a block by ID from its own resource and by number as the only block of a list starting at it.
+    fn get_block(&self, request_type: Message_MessageType, content: &[u8]) -> Result<validator::Message, Error> {
+        let (url, block_num) = if request_type == Message_MessageType::CLIENT_BLOCK_GET_BY_NUM_REQUEST {
+            let request = protobuf::parse_from_bytes::<ClientBlockGetByNumRequest>(content)
+                .map_err(|_| DeserializationError)?;
+            (format!("{}/blocks?start=0x{:016x}&limit=1", self.base_url, request.get_block_num()), Some(request.get_block_num()))
+        } else {
+            let request = protobuf::parse_from_bytes::<ClientBlockGetByIdRequest>(content)
+                .map_err(|_| DeserializationError)?;
+            (format!("{}/blocks/{}", self.base_url, request.get_block_id()), None)
+        };
+
+        let http_response = ureq::get(&url).call();
+        if http_response.synthetic() {
+            return Err(RequestError);
+        }
+
+        let mut response = ClientBlockGetResponse::new();
+        match http_response.status() {
+            200..=299 => {
+                let body: Value = http_response.into_json().map_err(|_| DeserializationError)?;
+                let block = match body.get("data") {
+                    Some(Value::Array(blocks)) => blocks.first().map(block_from).transpose()?,
+                    Some(block) => Some(block_from(block)?),
+                    None => return Err(DeserializationError)
+                };
+                let block = block.filter(|block| block_num.map_or(true, |block_num| block_num_of(block) == Some(block_num)));
+                match block {
+                    Some(block) => {
+                        self.remember(std::slice::from_ref(&block));
+                        response.set_status(ClientBlockGetResponse_Status::OK);
+                        response.set_block(block);
+                    },
+                    None => response.set_status(ClientBlockGetResponse_Status::NO_RESOURCE)
+                }
+            },
+            400 => response.set_status(ClientBlockGetResponse_Status::INVALID_ID),
+            404 => response.set_status(ClientBlockGetResponse_Status::NO_RESOURCE),
+            _ => response.set_status(ClientBlockGetResponse_Status::INTERNAL_ERROR)
+        }
+        transport::response_message(Message_MessageType::CLIENT_BLOCK_GET_RESPONSE, &response)
+    }
+
+    fn remember(&self, blocks: &[Block]) {
+        let mut block_ids = self.block_ids.borrow_mut();
+        for block in blocks {
+            if let Ok(header) = protobuf::parse_from_bytes::<BlockHeader>(block.get_header()) {
+                block_ids.insert(header.get_state_root_hash().to_string(), block.get_header_signature().to_string());
+            }
+        }
+    }
+
+    /// Looks the block of a state root up, the whole chain is only listed for state roots of unseen blocks.
+    fn head_for(&self, state_root: &str) -> Result<Option<String>, Error> {
+        if !self.block_ids.borrow().contains_key(state_root) {
+            let blocks = self.get_all_pages(&format!("{}/blocks", self.base_url))?.iter()
+                .map(block_from)
+                .collect::<Result<Vec<Block>, Error>>()?;
+            self.remember(&blocks);
+        }
+        Ok(self.block_ids.borrow().get(state_root).cloned())
+    }
+
+    fn get_all_pages(&self, url: &str) -> Result<Vec<Value>, Error> {
+        let mut items = Vec::new();
+        let mut next_page = Some(url.to_string());
+
+        while let Some(url) = next_page {
+            let page = self.get_json(&url)?;
+            match page.get("data") {
+                Some(Value::Array(data)) => items.extend(data.iter().cloned()),
+                _ => return Err(DeserializationError)
+            }
+            next_page = page.get("paging")
+                .and_then(|paging| paging.get("next"))
+                .and_then(|next| next.as_str())
+                .map(|next| next.to_string());
+        }
+
+        Ok(items)
+    }
+
+    fn get_json(&self, url: &str) -> Result<Value, Error> {
+        let http_response = ureq::get(url).call();
+        if http_response.synthetic() || http_response.error() {
+            return Err(RequestError);
+        }
+        http_response.into_json().map_err(|_| DeserializationError)
+    }
+}
+
+impl Transport for RestTransport {
+    fn send(&self, request_type: Message_MessageType, content: &[u8]) -> Result<validator::Message, Error> {
+        match request_type {
+            Message_MessageType::CLIENT_STATE_LIST_REQUEST => self.list_state_entries(content),
+            Message_MessageType::CLIENT_STATE_GET_REQUEST => self.get_state(content),
+            Message_MessageType::CLIENT_BATCH_SUBMIT_REQUEST => self.submit_batches(content),
+            Message_MessageType::CLIENT_TRANSACTION_LIST_REQUEST => self.list_transactions(),
+            Message_MessageType::CLIENT_BATCH_LIST_REQUEST => self.list_batches(),
+            Message_MessageType::CLIENT_RECEIPT_GET_REQUEST => self.get_receipts(content),
+            Message_MessageType::CLIENT_STATUS_GET_REQUEST => self.get_status(),
+            Message_MessageType::CLIENT_PEERS_GET_REQUEST => self.get_peers(),
+            Message_MessageType::CLIENT_BLOCK_LIST_REQUEST => self.list_blocks(content),
+            Message_MessageType::CLIENT_BLOCK_GET_BY_ID_REQUEST | Message_MessageType::CLIENT_BLOCK_GET_BY_NUM_REQUEST =>
+                self.get_block(request_type, content),
+            unsupported => Err(UnsupportedRequest(unsupported.descriptor().name().to_string()))
+        }
+    }
+}
+
+fn transaction_from(json: &Value) -> Result<Transaction, Error> {
+    let header = json.get("header").ok_or(DeserializationError)?;
+    let mut transaction = Transaction::new();
+    transaction.set_header(transaction_header_from(header)?.write_to_bytes()
+        .map_err(|_| SerializationError("Transaction Header".to_string()))?);
+    transaction.set_header_signature(string_field(json, "header_signature")?);
+    transaction.set_payload(base64_field(json, "payload")?);
+    Ok(transaction)
+}
+
+/// Converts a block, the REST API reports the block number as a string.
+fn block_from(json: &Value) -> Result<Block, Error> {
+    let header_json = json.get("header").ok_or(DeserializationError)?;
+    let block_num = match header_json.get("block_num") {
+        Some(Value::String(block_num)) => block_num.parse::<u64>().map_err(|_| DeserializationError)?,
+        Some(Value::Number(block_num)) => block_num.as_u64().ok_or(DeserializationError)?,
+        _ => return Err(DeserializationError)
+    };
+
+    let mut header = BlockHeader::new();
+    header.set_block_num(block_num);
+    header.set_previous_block_id(string_field(header_json, "previous_block_id")?);
+    header.set_signer_public_key(string_field(header_json, "signer_public_key")?);
+    header.set_batch_ids(protobuf::RepeatedField::from_vec(string_list_field(header_json, "batch_ids")?));
+    header.set_state_root_hash(string_field(header_json, "state_root_hash")?);
+
+    let batches = list_field(json, "batches")?.iter()
+        .map(batch_from)
+        .collect::<Result<Vec<Batch>, Error>>()?;
+
+    let mut block = Block::new();
+    block.set_header(header.write_to_bytes().map_err(|_| SerializationError("Block Header".to_string()))?);
+    block.set_header_signature(string_field(json, "header_signature")?);
+    block.set_batches(protobuf::RepeatedField::from_vec(batches));
+    Ok(block)
+}
+
+fn block_num_of(block: &Block) -> Option<u64> {
+    protobuf::parse_from_bytes::<BlockHeader>(block.get_header()).ok().map(|header| header.get_block_num())
+}
+
+fn batch_from(json: &Value) -> Result<Batch, Error> {
+    let header = json.get("header").ok_or(DeserializationError)?;
+    let transactions = match json.get("transactions") {
+        Some(Value::Array(transactions)) => transactions.iter()
+            .map(transaction_from)
+            .collect::<Result<Vec<Transaction>, Error>>()?,
+        _ => return Err(DeserializationError)
+    };
+
+    let mut batch = Batch::new();
+    batch.set_header(batch_header_from(header)?.write_to_bytes()
+        .map_err(|_| SerializationError("Batch Header".to_string()))?);
+    batch.set_header_signature(string_field(json, "header_signature")?);
+    batch.set_transactions(protobuf::RepeatedField::from_vec(transactions));
+    Ok(batch)
+}
+
+fn batch_header_from(json: &Value) -> Result<BatchHeader, Error> {
+    let mut header = BatchHeader::new();
+    header.set_signer_public_key(string_field(json, "signer_public_key")?);
+    header.set_transaction_ids(protobuf::RepeatedField::from_vec(string_list_field(json, "transaction_ids")?));
+    Ok(header)
+}
+
+fn transaction_header_from(json: &Value) -> Result<TransactionHeader, Error> {
+    let mut header = TransactionHeader::new();
+    header.set_batcher_public_key(string_field(json, "batcher_public_key")?);
+    header.set_dependencies(protobuf::RepeatedField::from_vec(string_list_field(json, "dependencies")?));
+    header.set_family_name(string_field(json, "family_name")?);
+    header.set_family_version(string_field(json, "family_version")?);
+    header.set_inputs(protobuf::RepeatedField::from_vec(string_list_field(json, "inputs")?));
+    header.set_nonce(string_field(json, "nonce")?);
+    header.set_outputs(protobuf::RepeatedField::from_vec(string_list_field(json, "outputs")?));
+    header.set_payload_sha512(string_field(json, "payload_sha512")?);
+    header.set_signer_public_key(string_field(json, "signer_public_key")?);
+    Ok(header)
+}
+
+fn receipt_from(json: &Value) -> Result<TransactionReceipt, Error> {
+    let mut state_changes = Vec::new();
+    for item in list_field(json, "state_changes")? {
+        let mut state_change = StateChange::new();
+        state_change.set_address(string_field(item, "address")?);
+        state_change.set_field_type(match string_field(item, "type")?.as_str() {
+            "SET" => StateChange_Type::SET,
+            "DELETE" => StateChange_Type::DELETE,
+            _ => StateChange_Type::TYPE_UNSET
+        });
+        if item.get("value").is_some() {
+            state_change.set_value(base64_field(item, "value")?);
+        }
+        state_changes.push(state_change);
+    }
+
+    let mut events = Vec::new();
+    for item in list_field(json, "events")? {
+        let mut event = Event::new();
+        event.set_event_type(string_field(item, "event_type")?);
+        let mut attributes = Vec::new();
+        for attribute_item in list_field(item, "attributes")? {
+            let mut attribute = Event_Attribute::new();
+            attribute.set_key(string_field(attribute_item, "key")?);
+            attribute.set_value(string_field(attribute_item, "value")?);
+            attributes.push(attribute);
+        }
+        event.set_attributes(protobuf::RepeatedField::from_vec(attributes));
+        if item.get("data").is_some() {
+            event.set_data(base64_field(item, "data")?);
+        }
+        events.push(event);
+    }
+
+    let data = list_field(json, "data")?.iter()
+        .map(|item| item.as_str()
+            .and_then(|encoded| data_encoding::BASE64.decode(encoded.as_bytes()).ok())
+            .ok_or(DeserializationError))
+        .collect::<Result<Vec<Vec<u8>>, Error>>()?;
+
+    let mut receipt = TransactionReceipt::new();
+    receipt.set_transaction_id(string_field(json, "transaction_id")?);
+    receipt.set_state_changes(protobuf::RepeatedField::from_vec(state_changes));
+    receipt.set_events(protobuf::RepeatedField::from_vec(events));
+    receipt.set_data(protobuf::RepeatedField::from_vec(data));
+    Ok(receipt)
+}
+
+fn list_field<'v>(json: &'v Value, name: &str) -> Result<&'v [Value], Error> {
+    match json.get(name) {
+        Some(Value::Array(values)) => Ok(values),
+        None => Ok(&[]),
+        _ => Err(DeserializationError)
+    }
+}
+
+fn string_field(json: &Value, name: &str) -> Result<String, Error> {
+    json.get(name)
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+        .ok_or(DeserializationError)
+}
+
+fn string_list_field(json: &Value, name: &str) -> Result<Vec<String>, Error> {
+    match json.get(name) {
+        Some(Value::Array(values)) => values.iter()
+            .map(|value| value.as_str().map(|value| value.to_string()).ok_or(DeserializationError))
+            .collect(),
+        None => Ok(Vec::new()),
+        _ => Err(DeserializationError)
+    }
+}
+
+fn base64_field(json: &Value, name: &str) -> Result<Vec<u8>, Error> {
+    let encoded = string_field(json, name)?;
+    data_encoding::BASE64.decode(encoded.as_bytes()).map_err(|_| DeserializationError)
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::thread;
+    use sawtooth_sdk::signing;
+    use sawtooth_sdk::messages::validator::Message_MessageType;
+    use sawtooth_sdk::messages::batch::{Batch, BatchHeader};
+    use sawtooth_sdk::messages::transaction::TransactionHeader;
+    use sawtooth_sdk::messages::client_state::{ClientStateListRequest, ClientStateListResponse, ClientStateGetRequest,
+                                               ClientStateGetResponse, ClientStateGetResponse_Status};
+    use sawtooth_sdk::messages::client_batch_submit::{ClientBatchSubmitRequest, ClientBatchSubmitResponse,
+                                                      ClientBatchSubmitResponse_Status};
+    use sawtooth_sdk::messages::client_block::{ClientBlockListRequest, ClientBlockListResponse, ClientBlockGetByIdRequest,
+                                               ClientBlockGetByNumRequest, ClientBlockGetResponse, ClientBlockGetResponse_Status};
+    use sawtooth_sdk::messages::client_list_control::ClientPagingControls;
+    use sawtooth_sdk::messages::client_receipt::{ClientReceiptGetRequest, ClientReceiptGetResponse};
+    use sawtooth_sdk::messages::transaction_receipt::StateChange_Type;
+    use protobuf::Message;
+    use serde_json::{json, Value};
+    use crate::sawtooth::helper;
+    use crate::sawtooth::transport::Transport;
+    use crate::sawtooth::transport::rest::{batch_from, RestTransport};
+
+    /// Answers one request with each response, in order, and passes on the request lines.
+    fn serve<F>(responses: F) -> (String, mpsc::Receiver<String>)
+        where F: FnOnce(&str) -> Vec<(u16, String)> {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind test server");
+        let url = format!("http://{}", listener.local_addr().expect("Test server has no address"));
+        let responses = responses(&url);
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || for (status, body) in responses {
+            let (stream, _) = listener.accept().expect("No connection to test server");
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).expect("Could not read request");
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).expect("Could not read request");
+                if header.trim().is_empty() {
+                    break;
+                }
+                let header = header.to_lowercase();
+                if let Some(length) = header.strip_prefix("content-length:") {
+                    content_length = length.trim().parse().expect("Invalid content length");
+                }
+            }
+            let mut request_body = vec![0u8; content_length];
+            reader.read_exact(&mut request_body).expect("Could not read request body");
+            let _ = sender.send(request_line.trim().to_string());
+
+            let response = format!("HTTP/1.1 {} Test\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                                   status, body.len(), body);
+            reader.into_inner().write_all(response.as_bytes()).expect("Could not answer request");
+        });
+
+        (url, receiver)
+    }
+
+    fn block_json(block_num: u64, state_root: &str) -> Value {
+        json!({
+            "header": {"block_num": block_num.to_string(), "previous_block_id": format!("block{}", block_num - 1),
+                       "signer_public_key": "02ab", "batch_ids": [], "state_root_hash": state_root},
+            "header_signature": format!("block{}", block_num),
+            "batches": []
+        })
+    }
+
+    #[test]
+    fn it_converts_rest_state_entries_into_a_state_list_response() {
+        let (url, _) = serve(|_| vec![(200, r#"{"data": [{"address": "abcdef00", "data": "YWdlbnQx"}], "paging": {}}"#.to_string())]);
+        let request = ClientStateListRequest::new().write_to_bytes().unwrap();
+
+        let response = RestTransport::new(&url)
+            .send(Message_MessageType::CLIENT_STATE_LIST_REQUEST, &request)
+            .expect("Request failed");
+        let state = protobuf::parse_from_bytes::<ClientStateListResponse>(response.get_content()).unwrap();
+
+        assert_eq!(response.get_message_type(), Message_MessageType::CLIENT_STATE_LIST_RESPONSE);
+        assert_eq!(state.get_entries().len(), 1);
+        assert_eq!(state.get_entries()[0].get_address(), "abcdef00");
+        assert_eq!(state.get_entries()[0].get_data(), "agent1".as_bytes());
+    }
+
+    #[test]
+    fn it_follows_the_next_page_of_state_listings() {
+        let (url, requests) = serve(|url| vec![
+            (200, json!({"data": [{"address": "abcdef00", "data": "YWdlbnQx"}],
+                         "paging": {"next": format!("{}/state?start=abcdef01", url)}}).to_string()),
+            (200, json!({"data": [{"address": "abcdef01", "data": "YWdlbnQy"}], "paging": {}}).to_string())
+        ]);
+        let request = ClientStateListRequest::new().write_to_bytes().unwrap();
+
+        let response = RestTransport::new(&url)
+            .send(Message_MessageType::CLIENT_STATE_LIST_REQUEST, &request)
+            .expect("Request failed");
+        let state = protobuf::parse_from_bytes::<ClientStateListResponse>(response.get_content()).unwrap();
+
+        let addresses: Vec<&str> = state.get_entries().iter().map(|entry| entry.get_address()).collect();
+        assert_eq!(addresses, vec!["abcdef00", "abcdef01"]);
+        assert_eq!(requests.iter().collect::<Vec<String>>(),
+                   vec!["GET /state HTTP/1.1", "GET /state?start=abcdef01 HTTP/1.1"]);
+    }
+
+    #[test]
+    fn it_lists_the_state_at_the_block_of_a_state_root() {
+        let (url, requests) = serve(|_| vec![
+            (200, json!({"data": [block_json(3, "root3"), block_json(2, "root2")], "paging": {}}).to_string()),
+            (200, json!({"data": [{"address": "abcdef00", "data": "YWdlbnQx"}], "paging": {}}).to_string())
+        ]);
+        let mut request = ClientStateListRequest::new();
+        request.set_state_root("root2".to_string());
+        request.set_address("abcdef".to_string());
+        let request = request.write_to_bytes().unwrap();
+
+        let response = RestTransport::new(&url)
+            .send(Message_MessageType::CLIENT_STATE_LIST_REQUEST, &request)
+            .expect("Request failed");
+        let state = protobuf::parse_from_bytes::<ClientStateListResponse>(response.get_content()).unwrap();
+
+        assert_eq!(state.get_entries()[0].get_address(), "abcdef00");
+        assert_eq!(requests.iter().collect::<Vec<String>>(),
+                   vec!["GET /blocks HTTP/1.1", "GET /state?head=block2&address=abcdef HTTP/1.1"]);
+    }
+
+    #[test]
+    fn it_gets_blocks_by_id_and_number_and_reads_state_at_them() {
+        let (url, requests) = serve(|_| vec![
+            (200, json!({"data": block_json(3, "root3")}).to_string()),
+            (200, json!({"data": [block_json(2, "root2")], "paging": {}}).to_string()),
+            (404, json!({"error": {}}).to_string()),
+            (200, json!({"data": "YWdlbnQx"}).to_string())
+        ]);
+        let transport = RestTransport::new(&url);
+        let mut by_id = ClientBlockGetByIdRequest::new();
+        by_id.set_block_id("block3".to_string());
+        let mut by_num = ClientBlockGetByNumRequest::new();
+        by_num.set_block_num(2);
+        let mut state_request = ClientStateGetRequest::new();
+        state_request.set_address("abcdef00".to_string());
+        state_request.set_state_root("root3".to_string());
+
+        let blocks: Vec<ClientBlockGetResponse> = vec![
+            transport.send(Message_MessageType::CLIENT_BLOCK_GET_BY_ID_REQUEST, &by_id.write_to_bytes().unwrap()),
+            transport.send(Message_MessageType::CLIENT_BLOCK_GET_BY_NUM_REQUEST, &by_num.write_to_bytes().unwrap()),
+            transport.send(Message_MessageType::CLIENT_BLOCK_GET_BY_ID_REQUEST, &by_id.write_to_bytes().unwrap())
+        ].into_iter()
+            .map(|response| protobuf::parse_from_bytes(response.expect("Request failed").get_content()).unwrap())
+            .collect();
+        let state = transport.send(Message_MessageType::CLIENT_STATE_GET_REQUEST, &state_request.write_to_bytes().unwrap())
+            .expect("Request failed");
+        let state = protobuf::parse_from_bytes::<ClientStateGetResponse>(state.get_content()).unwrap();
+
+        assert_eq!(blocks[0].get_block().get_header_signature(), "block3");
+        assert_eq!(blocks[1].get_block().get_header_signature(), "block2");
+        assert_eq!(blocks[2].get_status(), ClientBlockGetResponse_Status::NO_RESOURCE);
+        assert_eq!(state.get_status(), ClientStateGetResponse_Status::OK);
+        assert_eq!(state.get_value(), "agent1".as_bytes());
+        assert_eq!(requests.iter().collect::<Vec<String>>(), vec![
+            "GET /blocks/block3 HTTP/1.1",
+            "GET /blocks?start=0x0000000000000002&limit=1 HTTP/1.1",
+            "GET /blocks/block3 HTTP/1.1",
+            "GET /state/abcdef00?head=block3 HTTP/1.1"
+        ]);
+    }
+
+    #[test]
+    fn it_maps_the_http_status_of_batch_submissions() {
+        let (url, requests) = serve(|_| vec![(202, "{}".to_string()), (429, "{}".to_string()), (400, "{}".to_string())]);
+        let transport = RestTransport::new(&url);
+        let mut request = ClientBatchSubmitRequest::new();
+        request.set_batches(protobuf::RepeatedField::from_vec(vec![Batch::new()]));
+        let request = request.write_to_bytes().unwrap();
+
+        let statuses: Vec<ClientBatchSubmitResponse_Status> = (0..3)
+            .map(|_| transport.send(Message_MessageType::CLIENT_BATCH_SUBMIT_REQUEST, &request).expect("Request failed"))
+            .map(|response| protobuf::parse_from_bytes::<ClientBatchSubmitResponse>(response.get_content()).unwrap().get_status())
+            .collect();
+
+        assert_eq!(statuses, vec![ClientBatchSubmitResponse_Status::OK, ClientBatchSubmitResponse_Status::QUEUE_FULL,
+                                  ClientBatchSubmitResponse_Status::INVALID_BATCH]);
+        assert_eq!(requests.recv().unwrap(), "POST /batches HTTP/1.1");
+    }
+
+    #[test]
+    fn it_passes_the_paging_of_block_lists_on() {
+        let (url, requests) = serve(|_| vec![(200, json!({
+            "data": [{
+                "header": {"block_num": "3", "previous_block_id": "block2", "signer_public_key": "02ab",
+                           "batch_ids": [], "state_root_hash": "root3"},
+                "header_signature": "block3",
+                "batches": []
+            }],
+            "head": "block3",
+            "paging": {"next_position": "0x0002"}
+        }).to_string())]);
+        let mut paging = ClientPagingControls::new();
+        paging.set_limit(1);
+        paging.set_start("0x0003".to_string());
+        let mut request = ClientBlockListRequest::new();
+        request.set_paging(paging);
+        let request = request.write_to_bytes().unwrap();
+
+        let response = RestTransport::new(&url)
+            .send(Message_MessageType::CLIENT_BLOCK_LIST_REQUEST, &request)
+            .expect("Request failed");
+        let blocks = protobuf::parse_from_bytes::<ClientBlockListResponse>(response.get_content()).unwrap();
+
+        assert_eq!(requests.recv().unwrap(), "GET /blocks?limit=1&start=0x0003 HTTP/1.1");
+        assert_eq!(blocks.get_head_id(), "block3");
+        assert_eq!(blocks.get_paging().get_next(), "0x0002");
+        assert_eq!(blocks.get_blocks().len(), 1);
+        assert_eq!(blocks.get_blocks()[0].get_header_signature(), "block3");
+    }
+
+    #[test]
+    fn it_converts_rest_receipts_into_a_receipt_response() {
+        let (url, requests) = serve(|_| vec![(200, json!({"data": [{
+            "transaction_id": "transaction1",
+            "state_changes": [{"address": "abcdef00", "value": "YWdlbnQx", "type": "SET"}],
+            "events": [{"event_type": "alica_messages/recorded", "attributes": [{"key": "agent_id", "value": "agent1"}]}],
+            "data": []
+        }]}).to_string())]);
+        let mut request = ClientReceiptGetRequest::new();
+        request.set_transaction_ids(protobuf::RepeatedField::from_vec(vec!["transaction1".to_string()]));
+        let request = request.write_to_bytes().unwrap();
+
+        let response = RestTransport::new(&url)
+            .send(Message_MessageType::CLIENT_RECEIPT_GET_REQUEST, &request)
+            .expect("Request failed");
+        let receipts = protobuf::parse_from_bytes::<ClientReceiptGetResponse>(response.get_content()).unwrap();
+
+        assert_eq!(requests.recv().unwrap(), "GET /receipts?id=transaction1 HTTP/1.1");
+        let receipt = &receipts.get_receipts()[0];
+        assert_eq!(receipt.get_transaction_id(), "transaction1");
+        assert_eq!(receipt.get_state_changes()[0].get_field_type(), StateChange_Type::SET);
+        assert_eq!(receipt.get_state_changes()[0].get_value(), "agent1".as_bytes());
+        assert_eq!(receipt.get_events()[0].get_attributes()[0].get_value(), "agent1");
+    }
+
+    #[test]
+    fn it_restores_header_bytes_that_match_their_signatures() {
+        let context = signing::create_context("secp256k1").expect("No secp256k1 context");
+        let private_key = context.new_random_private_key().expect("Could not create private key");
+        let signer = signing::Signer::new_boxed(context, private_key);
+        let public_key = signer.get_public_key().expect("Signer has no public key").as_hex();
+        let mut transaction_header = TransactionHeader::new();
+        transaction_header.set_batcher_public_key(public_key.clone());
+        transaction_header.set_dependencies(protobuf::RepeatedField::from_vec(vec!["a".repeat(128)]));
+        transaction_header.set_family_name("alica_messages".to_string());
+        transaction_header.set_family_version("0.1.0".to_string());
+        transaction_header.set_inputs(protobuf::RepeatedField::from_vec(vec!["abcdef00".to_string(), "abcdef01".to_string()]));
+        transaction_header.set_nonce("nonce".to_string());
+        transaction_header.set_outputs(protobuf::RepeatedField::from_vec(vec!["abcdef00".to_string()]));
+        transaction_header.set_payload_sha512(helper::calculate_checksum(&"payload".as_bytes()));
+        transaction_header.set_signer_public_key(public_key.clone());
+        let transaction_header_bytes = transaction_header.write_to_bytes().unwrap();
+        let transaction_id = signer.sign(&transaction_header_bytes).expect("Could not sign transaction header");
+        let mut batch_header = BatchHeader::new();
+        batch_header.set_signer_public_key(public_key.clone());
+        batch_header.set_transaction_ids(protobuf::RepeatedField::from_vec(vec![transaction_id.clone()]));
+        let batch_header_bytes = batch_header.write_to_bytes().unwrap();
+        let batch_id = signer.sign(&batch_header_bytes).expect("Could not sign batch header");
+        let json = json!({
+            "header": {"signer_public_key": public_key, "transaction_ids": [transaction_id]},
+            "header_signature": batch_id,
+            "transactions": [{
+                "header": {
+                    "batcher_public_key": public_key,
+                    "dependencies": ["a".repeat(128)],
+                    "family_name": "alica_messages",
+                    "family_version": "0.1.0",
+                    "inputs": ["abcdef00", "abcdef01"],
+                    "nonce": "nonce",
+                    "outputs": ["abcdef00"],
+                    "payload_sha512": helper::calculate_checksum(&"payload".as_bytes()),
+                    "signer_public_key": public_key
+                },
+                "header_signature": transaction_id,
+                "payload": data_encoding::BASE64.encode("payload".as_bytes())
+            }]
+        });
+
+        let batch = batch_from(&json).expect("Could not convert batch");
+
+        assert_eq!(batch.get_header(), batch_header_bytes.as_slice());
+        assert_eq!(batch.get_transactions()[0].get_header(), transaction_header_bytes.as_slice());
+        assert!(helper::verify_signature(batch.get_header_signature(), batch.get_header(), &public_key));
+        let transaction = &batch.get_transactions()[0];
+        assert!(helper::verify_signature(transaction.get_header_signature(), transaction.get_header(), &public_key));
+    }
+
+    #[test]
+    fn it_rejects_requests_without_a_rest_counterpart() {
+        let transport = RestTransport::new("http://127.0.0.1:1");
+
+        let result = transport.send(Message_MessageType::CLIENT_EVENTS_SUBSCRIBE_REQUEST, &[]);
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,36 @@
+use sawtooth_sdk::messaging::stream::{MessageConnection, MessageSender};
+use sawtooth_sdk::messaging::zmq_stream::{ZmqMessageConnection, ZmqMessageSender};
+use sawtooth_sdk::messages::validator::{self, Message_MessageType};
+use crate::sawtooth::Error::{RequestError, ResponseError};
+use crate::sawtooth::Error;
+use crate::sawtooth::transport::Transport;
+
+pub struct ZmqTransport {
+    sender: ZmqMessageSender
+}
+
+impl ZmqTransport {
+    pub fn new(url: &str) -> Self {
+        let connection = ZmqMessageConnection::new(url).create();
+        ZmqTransport {
+            sender: connection.0
+        }
+    }
+}
+
+impl Transport for ZmqTransport {
+    fn send(&self, request_type: Message_MessageType, content: &[u8]) -> Result<validator::Message, Error> {
+        let correlation_id = uuid::Uuid::new_v4().to_string();
+
+        self.sender.send(request_type, &correlation_id, content)
+            .map(|mut future| future.get())
+            .map_err(|_| ResponseError)?
+            .map_err(|_| RequestError)
+    }
+}
+
+impl Drop for ZmqTransport {
+    fn drop(&mut self) {
+        self.sender.close();
+    }
+}
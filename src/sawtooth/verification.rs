@@ -0,0 +1,130 @@
+use sawtooth_sdk::messages::batch::{Batch, BatchHeader};
+use sawtooth_sdk::messages::transaction::{Transaction, TransactionHeader};
+use crate::sawtooth::helper;
+
+#[derive(Debug, PartialEq)]
+pub enum Violation {
+    MalformedHeader,
+    InvalidSignature,
+    PayloadChecksumMismatch,
+    TransactionIdsMismatch,
+    BatcherKeyMismatch(String),
+    DuplicateTransaction(String),
+    InvalidTransaction(String, Box<Violation>)
+}
+
+impl Violation {
+    pub fn description(&self) -> String {
+        match self {
+            Violation::MalformedHeader => "Header could not be decoded".to_string(),
+            Violation::InvalidSignature => "Header signature does not match the signer public key".to_string(),
+            Violation::PayloadChecksumMismatch => "Payload does not match the payload_sha512 of the header".to_string(),
+            Violation::TransactionIdsMismatch => "Header does not list the IDs of the contained transactions".to_string(),
+            Violation::BatcherKeyMismatch(transaction_id) =>
+                format!("Transaction {} names another batcher public key than the batch signer", transaction_id),
+            Violation::DuplicateTransaction(transaction_id) =>
+                format!("Transaction {} is contained more than once", transaction_id),
+            Violation::InvalidTransaction(transaction_id, violation) =>
+                format!("Transaction {}: {}", transaction_id, violation.description())
+        }
+    }
+}
+
+pub fn verify_transaction(transaction: &Transaction) -> Vec<Violation> {
+    let header = match protobuf::parse_from_bytes::<TransactionHeader>(transaction.get_header()) {
+        Ok(header) => header,
+        Err(_) => return vec![Violation::MalformedHeader]
+    };
+
+    let mut violations = Vec::new();
+    if !helper::verify_signature(transaction.get_header_signature(), transaction.get_header(),
+                                 header.get_signer_public_key()) {
+        violations.push(Violation::InvalidSignature);
+    }
+    if helper::calculate_checksum(&transaction.get_payload()) != header.get_payload_sha512() {
+        violations.push(Violation::PayloadChecksumMismatch);
+    }
+    violations
+}
+
+pub fn verify_batch(batch: &Batch) -> Vec<Violation> {
+    let header = match protobuf::parse_from_bytes::<BatchHeader>(batch.get_header()) {
+        Ok(header) => header,
+        Err(_) => return vec![Violation::MalformedHeader]
+    };
+
+    let mut violations = Vec::new();
+    if !helper::verify_signature(batch.get_header_signature(), batch.get_header(), header.get_signer_public_key()) {
+        violations.push(Violation::InvalidSignature);
+    }
+
+    let transaction_ids: Vec<&str> = batch.get_transactions().iter()
+        .map(|transaction| transaction.get_header_signature())
+        .collect();
+    if header.get_transaction_ids().iter().map(|id| id.as_str()).collect::<Vec<&str>>() != transaction_ids {
+        violations.push(Violation::TransactionIdsMismatch);
+    }
+
+    for (index, transaction) in batch.get_transactions().iter().enumerate() {
+        let transaction_id = transaction.get_header_signature().to_string();
+        if transaction_ids[..index].contains(&transaction_id.as_str()) {
+            violations.push(Violation::DuplicateTransaction(transaction_id));
+            continue;
+        }
+        let batcher_public_key = protobuf::parse_from_bytes::<TransactionHeader>(transaction.get_header())
+            .map(|transaction_header| transaction_header.get_batcher_public_key().to_string());
+        if let Ok(batcher_public_key) = batcher_public_key {
+            if batcher_public_key != header.get_signer_public_key() {
+                violations.push(Violation::BatcherKeyMismatch(transaction_id.clone()));
+            }
+        }
+        violations.extend(verify_transaction(transaction).into_iter()
+            .map(|violation| Violation::InvalidTransaction(transaction_id.clone(), Box::new(violation))));
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod test {
+    use sawtooth_sdk::signing;
+    use sawtooth_sdk::messages::batch::Batch;
+    use sawtooth_alica_payload::{payloads, TransactionFamily};
+    use crate::sawtooth::{TransactionFactory, BatchFactory};
+    use crate::sawtooth::factory::GeneralPurposeComponentFactory;
+    use crate::sawtooth::verification::{verify_batch, Violation};
+
+    fn create_test_batch() -> Batch {
+        let context = signing::create_context("secp256k1").expect("No secp256k1 context");
+        let private_key = context.new_random_private_key().expect("Could not create private key");
+        let family = TransactionFamily::new("alica_messages", &vec!["0.1.0".to_string()]);
+        let format = payloads::pipe_separated::Format::default();
+        let factory = GeneralPurposeComponentFactory::new(&family, &format, signing::Signer::new_boxed(context, private_key));
+        let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000);
+
+        let header = factory.create_transaction_header_for(&payload).expect("Could not create header");
+        let transactions = vec![factory.create_transaction_for(&payload, &header).expect("Could not create transaction")];
+        let batch_header = factory.create_batch_header_for(&transactions).expect("Could not create batch header");
+        factory.create_batch_for(&transactions, &batch_header).expect("Could not create batch")
+    }
+
+    #[test]
+    fn it_finds_no_violations_in_batches_created_by_the_factory() {
+        let batch = create_test_batch();
+
+        assert_eq!(verify_batch(&batch), Vec::new());
+    }
+
+    #[test]
+    fn it_detects_altered_payloads_and_transaction_lists() {
+        let mut batch = create_test_batch();
+        let transaction_id = batch.get_transactions()[0].get_header_signature().to_string();
+        batch.mut_transactions()[0].set_payload("agent2|AlicaEngineInfo|message|1605000000".as_bytes().to_vec());
+        let mut altered_batch = batch.clone();
+        altered_batch.mut_transactions()[0].set_header_signature("0".repeat(128));
+
+        assert_eq!(verify_batch(&batch),
+                   vec![Violation::InvalidTransaction(transaction_id, Box::new(Violation::PayloadChecksumMismatch))]);
+        assert!(verify_batch(&altered_batch).contains(&Violation::TransactionIdsMismatch));
+    }
+}
@@ -0,0 +1,170 @@
+use mockall::automock;
+use sawtooth_alica_payload::payloads;
+use sawtooth_alica_payload::payloads::TransactionPayload;
+use crate::alica::MessageTypeRegistry;
+
+#[derive(Debug)]
+pub enum Error {
+    InvalidField(String, String)
+}
+
+#[automock]
+pub trait PayloadValidator {
+    fn validate(&self, payload: &TransactionPayload) -> Result<(), Error>;
+}
+
+pub struct ReservedCharacterValidator {
+    format_name: String,
+    reserved_characters: Vec<char>
+}
+
+impl ReservedCharacterValidator {
+    pub fn new(format_name: &str, reserved_characters: &[char]) -> Self {
+        ReservedCharacterValidator {
+            format_name: format_name.to_string(),
+            reserved_characters: reserved_characters.to_vec()
+        }
+    }
+
+    fn check(&self, field: &str, value: &str) -> Result<(), Error> {
+        match value.chars().find(|character| self.reserved_characters.contains(character)) {
+            Some(character) => Err(Error::InvalidField(field.to_string(), format!(
+                "contains '{}' which is reserved by the {} payload format", character, self.format_name))),
+            None => Ok(())
+        }
+    }
+}
+
+impl PayloadValidator for ReservedCharacterValidator {
+    fn validate(&self, payload: &TransactionPayload) -> Result<(), Error> {
+        self.check("agent_id", &payload.agent_id)?;
+        self.check("message_type", &payload.message_type)?;
+        self.check("message", &String::from_utf8_lossy(&payload.message_bytes))
+    }
+}
+
+pub struct RoundTripValidator<'a> {
+    payload_format: &'a dyn payloads::Format
+}
+
+impl<'a> RoundTripValidator<'a> {
+    pub fn new(payload_format: &'a dyn payloads::Format) -> Self {
+        RoundTripValidator {
+            payload_format
+        }
+    }
+}
+
+impl<'a> PayloadValidator for RoundTripValidator<'a> {
+    fn validate(&self, payload: &TransactionPayload) -> Result<(), Error> {
+        let restored_payload = self.payload_format.serialize(payload)
+            .and_then(|bytes| self.payload_format.deserialize(&bytes))
+            .map_err(|_| Error::InvalidField("payload".to_string(), "does not survive serialization".to_string()))?;
+
+        let mismatched_field = if restored_payload.agent_id != payload.agent_id {
+            Some("agent_id")
+        } else if restored_payload.message_type != payload.message_type {
+            Some("message_type")
+        } else if restored_payload.message_bytes != payload.message_bytes {
+            Some("message")
+        } else if restored_payload.timestamp != payload.timestamp {
+            Some("timestamp")
+        } else {
+            None
+        };
+
+        match mismatched_field {
+            Some(field) => Err(Error::InvalidField(field.to_string(), "changes when serialized and deserialized again".to_string())),
+            None => Ok(())
+        }
+    }
+}
+
+pub struct MessageTypeValidator<'a> {
+    message_types: &'a MessageTypeRegistry,
+    allow_unknown_types: bool
+}
+
+impl<'a> MessageTypeValidator<'a> {
+    pub fn new(message_types: &'a MessageTypeRegistry, allow_unknown_types: bool) -> Self {
+        MessageTypeValidator {
+            message_types,
+            allow_unknown_types
+        }
+    }
+}
+
+impl<'a> PayloadValidator for MessageTypeValidator<'a> {
+    fn validate(&self, payload: &TransactionPayload) -> Result<(), Error> {
+        let message_type = &payload.message_type;
+
+        if !self.message_types.contains(message_type) {
+            if let Some(known_type) = self.message_types.find_similar(message_type) {
+                return Err(Error::InvalidField("message_type".to_string(), format!(
+                    "contains unknown type \"{}\", did you mean \"{}\"?", message_type, known_type)));
+            }
+            if !self.allow_unknown_types {
+                return Err(Error::InvalidField("message_type".to_string(), format!(
+                    "contains unknown type \"{}\", known types are {}", message_type, self.message_types.names().join(", "))));
+            }
+            return Ok(());
+        }
+
+        match self.message_types.schema_of(message_type) {
+            Some(schema) => schema.validate(&payload.message_bytes)
+                .map_err(|reason| Error::InvalidField("message".to_string(), reason)),
+            None => Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sawtooth_alica_payload::payloads::{self, TransactionPayload};
+    use crate::alica::MessageTypeRegistry;
+    use crate::validation::{Error, PayloadValidator, ReservedCharacterValidator, RoundTripValidator, MessageTypeValidator};
+
+    #[test]
+    fn it_names_the_field_containing_a_reserved_character() {
+        let payload = TransactionPayload::new("agent1", "type", "{\"a\": \"b|c\"}".as_bytes(), 69182798179);
+
+        let result = ReservedCharacterValidator::new("pipe-separated", &['|']).validate(&payload);
+
+        match result {
+            Err(Error::InvalidField(field, _)) => assert_eq!(field, "message"),
+            Ok(_) => panic!("Reserved character was not detected")
+        }
+    }
+
+    #[test]
+    fn it_accepts_payloads_without_reserved_characters() {
+        let payload = TransactionPayload::new("agent1", "type", "message".as_bytes(), 69182798179);
+
+        assert!(ReservedCharacterValidator::new("pipe-separated", &['|']).validate(&payload).is_ok());
+    }
+
+    #[test]
+    fn it_detects_payloads_that_do_not_survive_a_round_trip() {
+        let format = payloads::pipe_separated::Format::default();
+        let payload = TransactionPayload::new("agent|1", "type", "message".as_bytes(), 69182798179);
+
+        assert!(RoundTripValidator::new(&format).validate(&payload).is_err());
+    }
+
+    #[test]
+    fn it_rejects_misspelled_message_types_even_if_unknown_types_are_allowed() {
+        let message_types = MessageTypeRegistry::default();
+        let payload = TransactionPayload::new("agent1", "AlicaEngineinfo", "message".as_bytes(), 69182798179);
+
+        assert!(MessageTypeValidator::new(&message_types, true).validate(&payload).is_err());
+    }
+
+    #[test]
+    fn it_accepts_custom_message_types_only_if_allowed() {
+        let message_types = MessageTypeRegistry::default();
+        let payload = TransactionPayload::new("agent1", "CustomInfo", "message".as_bytes(), 69182798179);
+
+        assert!(MessageTypeValidator::new(&message_types, false).validate(&payload).is_err());
+        assert!(MessageTypeValidator::new(&message_types, true).validate(&payload).is_ok());
+    }
+}
@@ -0,0 +1,233 @@
+mod common;
+
+use sawtooth_sdk::messages::validator::Message_MessageType;
+use sawtooth_sdk::messages::transaction::TransactionHeader;
+use sawtooth_sdk::messages::batch::BatchHeader;
+use sawtooth_sdk::messages::client_state::{ClientStateGetResponse, ClientStateGetResponse_Status};
+use sawtooth_sdk::messages::client_batch_submit::{ClientBatchSubmitRequest, ClientBatchSubmitResponse_Status};
+use sawtooth_alica_payload::payloads::{self, Format};
+use alica_messages_client::command::{batch, SawtoothCommand};
+use alica_messages_client::sawtooth::{Client, TransactionFactory};
+use alica_messages_client::sawtooth::factory::{GeneralPurposeComponentFactory, NonceStrategy};
+use alica_messages_client::sawtooth::transport::memory::SimulatedLedger;
+use alica_messages_client::validation::{PayloadValidator, ReservedCharacterValidator};
+use common::fake_validator::FakeValidator;
+
+fn test_payload() -> payloads::TransactionPayload {
+    payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000)
+}
+
+#[test]
+fn create_submits_a_single_transaction_batch() {
+    let validator = FakeValidator::start();
+    validator.reply_with_batch_status(ClientBatchSubmitResponse_Status::OK);
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::validator_client(&validator, &family, &format);
+
+    let result = batch::CreateCommand::new(client, test_payload(), Vec::new()).execute();
+
+    assert!(result.is_ok());
+    let requests = validator.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].get_message_type(), Message_MessageType::CLIENT_BATCH_SUBMIT_REQUEST);
+    let request = protobuf::parse_from_bytes::<ClientBatchSubmitRequest>(requests[0].get_content()).unwrap();
+    assert_eq!(request.get_batches().len(), 1);
+    assert_eq!(request.get_batches()[0].get_transactions().len(), 1);
+    let payload = format.deserialize(request.get_batches()[0].get_transactions()[0].get_payload())
+        .ok().expect("Could not deserialize payload");
+    assert_eq!(payload.agent_id, "agent1");
+}
+
+#[test]
+fn create_fails_for_an_invalid_batch() {
+    let validator = FakeValidator::start();
+    validator.reply_with_batch_status(ClientBatchSubmitResponse_Status::INVALID_BATCH);
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::validator_client(&validator, &family, &format);
+
+    let result = batch::CreateCommand::new(client, test_payload(), Vec::new()).execute();
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn create_fails_if_the_queue_of_the_validator_is_full() {
+    let validator = FakeValidator::start();
+    validator.reply_with_batch_status(ClientBatchSubmitResponse_Status::QUEUE_FULL);
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::validator_client(&validator, &family, &format);
+
+    let result = batch::CreateCommand::new(client, test_payload(), Vec::new()).execute();
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn create_fails_on_a_wrong_response_type() {
+    let validator = FakeValidator::start();
+    validator.reply_with_wrong_type();
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::validator_client(&validator, &family, &format);
+
+    let result = batch::CreateCommand::new(client, test_payload(), Vec::new()).execute();
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn create_rejects_payloads_with_reserved_characters_before_submitting() {
+    let validator = FakeValidator::start();
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::validator_client(&validator, &family, &format);
+    let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "{\"plan\": \"a|b\"}".as_bytes(), 1605000000);
+    let validators: Vec<Box<dyn PayloadValidator>> = vec![Box::from(ReservedCharacterValidator::new("pipe-separated", &['|']))];
+
+    let result = batch::CreateCommand::new(client, payload, validators).execute();
+
+    assert!(result.is_err());
+    assert!(validator.requests().is_empty());
+}
+
+#[test]
+fn import_submits_one_batch_per_line_and_resolves_dependencies_on_earlier_labels() {
+    let validator = FakeValidator::start();
+    validator.reply_with_batch_status(ClientBatchSubmitResponse_Status::OK);
+    validator.reply_with_batch_status(ClientBatchSubmitResponse_Status::OK);
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::validator_client(&validator, &family, &format);
+    let input = [
+        r#"{"label": "talk", "agent_id": "agent1", "message_type": "SyncTalk", "message": "talk", "timestamp": 1}"#,
+        r#"{"agent_id": "agent1", "message_type": "SyncReady", "message": "ready", "timestamp": 2, "depends_on": ["talk"]}"#
+    ].join("\n");
+
+    let result = batch::ImportCommand::new(client, input, Vec::new()).execute();
+
+    assert!(result.is_ok());
+    let requests = validator.requests();
+    assert_eq!(requests.len(), 2);
+    let first_transaction = protobuf::parse_from_bytes::<ClientBatchSubmitRequest>(requests[0].get_content()).unwrap()
+        .get_batches()[0].get_transactions()[0].clone();
+    let second_transaction = protobuf::parse_from_bytes::<ClientBatchSubmitRequest>(requests[1].get_content()).unwrap()
+        .get_batches()[0].get_transactions()[0].clone();
+    let second_header = protobuf::parse_from_bytes::<TransactionHeader>(second_transaction.get_header()).unwrap();
+    assert_eq!(second_header.get_dependencies(), &[first_transaction.get_header_signature().to_string()]);
+}
+
+#[test]
+fn import_rejects_dependencies_on_unknown_labels_before_sending() {
+    let validator = FakeValidator::start();
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::validator_client(&validator, &family, &format);
+    let input = r#"{"agent_id": "agent1", "message_type": "SyncReady", "message": "ready", "timestamp": 2, "depends_on": ["talk"]}"#;
+
+    let result = batch::ImportCommand::new(client, input.to_string(), Vec::new()).execute();
+
+    assert!(result.is_err());
+    assert!(validator.requests().is_empty());
+}
+
+#[test]
+fn import_with_duplicate_check_resolves_dependencies_on_skipped_entries_to_the_recorded_transaction() {
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::memory_client(&family, &format);
+    let recorded_payload = payloads::TransactionPayload::new("agent1", "SyncTalk", "talk".as_bytes(), 1);
+    client.create_batch(&[&recorded_payload]).expect("Batch was rejected");
+    let input = [
+        r#"{"label": "talk", "agent_id": "agent1", "message_type": "SyncTalk", "message": "talk", "timestamp": 1}"#,
+        r#"{"agent_id": "agent1", "message_type": "SyncReady", "message": "ready", "timestamp": 2, "depends_on": ["talk"]}"#
+    ].join("\n");
+
+    let result = batch::ImportCommand::new(client, input, Vec::new())
+        .with_duplicate_check(batch::DuplicateCheck::new(&family, &format))
+        .execute();
+
+    assert!(result.is_ok());
+}
+
+#[test]
+fn wrap_submits_transactions_signed_by_another_key_in_a_batch_of_the_batcher() {
+    let validator = FakeValidator::start();
+    validator.reply_with_batch_status(ClientBatchSubmitResponse_Status::OK);
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let batcher = common::create_test_signer();
+    let batcher_public_key = batcher.get_public_key().expect("Batcher has no public key").as_hex();
+    let robot_factory = GeneralPurposeComponentFactory::new(&family, &format, common::create_test_signer())
+        .with_remote_batcher(&batcher_public_key);
+    let gateway_factory = GeneralPurposeComponentFactory::new(&family, &format, common::create_test_signer())
+        .with_batcher(batcher);
+    let header = robot_factory.create_transaction_header_for(&test_payload()).expect("Could not create header");
+    let transaction = robot_factory.create_transaction_for(&test_payload(), &header).expect("Could not create transaction");
+    let client = Client::new(validator.url(), &gateway_factory).expect("Could not connect");
+
+    let result = client.submit_transactions(vec![transaction]);
+
+    assert!(result.is_ok());
+    let request = protobuf::parse_from_bytes::<ClientBatchSubmitRequest>(validator.requests()[0].get_content()).unwrap();
+    let batch_header = protobuf::parse_from_bytes::<BatchHeader>(request.get_batches()[0].get_header()).unwrap();
+    assert_eq!(batch_header.get_signer_public_key(), batcher_public_key);
+    assert_ne!(header.get_signer_public_key(), batcher_public_key);
+}
+
+#[test]
+fn wrap_rejects_transactions_that_name_another_batcher_before_sending() {
+    let validator = FakeValidator::start();
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let robot_factory = GeneralPurposeComponentFactory::new(&family, &format, common::create_test_signer());
+    let gateway_factory = GeneralPurposeComponentFactory::new(&family, &format, common::create_test_signer());
+    let header = robot_factory.create_transaction_header_for(&test_payload()).expect("Could not create header");
+    let transaction = robot_factory.create_transaction_for(&test_payload(), &header).expect("Could not create transaction");
+    let client = Client::new(validator.url(), &gateway_factory).expect("Could not connect");
+
+    let result = client.submit_transactions(vec![transaction]);
+
+    assert!(result.is_err());
+    assert!(validator.requests().is_empty());
+}
+
+#[test]
+fn create_with_duplicate_check_skips_messages_that_are_already_recorded() {
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let factory = GeneralPurposeComponentFactory::new(&family, &format, common::create_test_signer())
+        .with_nonce_strategy(NonceStrategy::Derived);
+    let client = Client::with_transport(Box::new(SimulatedLedger::new(&family, &format)), &factory);
+    client.create_batch(&[&test_payload()]).expect("Batch was rejected");
+
+    let result = batch::CreateCommand::new(client, test_payload(), Vec::new())
+        .with_duplicate_check(batch::DuplicateCheck::new(&family, &format))
+        .execute();
+
+    assert!(result.is_ok());
+}
+
+#[test]
+fn create_with_duplicate_check_fails_if_another_message_is_recorded_at_the_address() {
+    let validator = FakeValidator::start();
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let recorded_payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "other".as_bytes(), 1605000000);
+    let mut response = ClientStateGetResponse::new();
+    response.set_status(ClientStateGetResponse_Status::OK);
+    response.set_value(format.serialize(&recorded_payload).ok().expect("Could not serialize payload"));
+    validator.reply_with(Message_MessageType::CLIENT_STATE_GET_RESPONSE, &response);
+    let client = common::validator_client(&validator, &family, &format);
+
+    let result = batch::CreateCommand::new(client, test_payload(), Vec::new())
+        .with_duplicate_check(batch::DuplicateCheck::new(&family, &format))
+        .execute();
+
+    assert!(result.is_err());
+    let requests = validator.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].get_message_type(), Message_MessageType::CLIENT_STATE_GET_REQUEST);
+}
@@ -0,0 +1,117 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use sawtooth_sdk::messages::validator::{Message, Message_MessageType};
+use sawtooth_sdk::messages::client_batch_submit::{ClientBatchSubmitResponse, ClientBatchSubmitResponse_Status};
+use sawtooth_sdk::messages::client_state::{ClientStateListResponse, ClientStateListResponse_Entry,
+                                           ClientStateListResponse_Status};
+
+type ScriptedReplies = VecDeque<(Message_MessageType, Vec<u8>)>;
+
+/// In-process stand-in for a Sawtooth validator that answers with scripted replies.
+pub struct FakeValidator {
+    url: String,
+    requests: Arc<Mutex<Vec<Message>>>,
+    replies: Arc<Mutex<ScriptedReplies>>,
+    running: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>
+}
+
+impl FakeValidator {
+    pub fn start() -> Self {
+        let context = zmq::Context::new();
+        let socket = context.socket(zmq::ROUTER).expect("Could not create ROUTER socket");
+        socket.bind("tcp://127.0.0.1:*").expect("Could not bind fake validator");
+        let url = socket.get_last_endpoint()
+            .expect("Could not read endpoint")
+            .expect("Endpoint is not valid UTF-8");
+
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let replies = Arc::new(Mutex::new(VecDeque::new()));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let worker = {
+            let requests = Arc::clone(&requests);
+            let replies = Arc::clone(&replies);
+            let running = Arc::clone(&running);
+            thread::spawn(move || {
+                let _context = context;
+                let socket = socket;
+                while running.load(Ordering::SeqCst) {
+                    if socket.poll(zmq::POLLIN, 50).expect("Polling failed") == 0 {
+                        continue;
+                    }
+                    let frames = socket.recv_multipart(0).expect("Could not receive request");
+                    let request = protobuf::parse_from_bytes::<Message>(&frames[frames.len() - 1])
+                        .expect("Request is not a validator message");
+
+                    let (message_type, content) = replies.lock().unwrap().pop_front()
+                        .unwrap_or((Message_MessageType::PING_RESPONSE, Vec::new()));
+                    let mut reply = Message::new();
+                    reply.set_message_type(message_type);
+                    reply.set_correlation_id(request.get_correlation_id().to_string());
+                    reply.set_content(content);
+                    requests.lock().unwrap().push(request);
+
+                    let reply = protobuf::Message::write_to_bytes(&reply).expect("Could not serialize reply");
+                    socket.send_multipart(vec![frames[0].clone(), reply], 0).expect("Could not send reply");
+                }
+            })
+        };
+
+        FakeValidator {
+            url,
+            requests,
+            replies,
+            running,
+            worker: Some(worker)
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn requests(&self) -> Vec<Message> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn reply_with(&self, message_type: Message_MessageType, response: &dyn protobuf::Message) {
+        let content = response.write_to_bytes().expect("Could not serialize scripted reply");
+        self.replies.lock().unwrap().push_back((message_type, content));
+    }
+
+    pub fn reply_with_batch_status(&self, status: ClientBatchSubmitResponse_Status) {
+        let mut response = ClientBatchSubmitResponse::new();
+        response.set_status(status);
+        self.reply_with(Message_MessageType::CLIENT_BATCH_SUBMIT_RESPONSE, &response);
+    }
+
+    pub fn reply_with_state_entries(&self, entries: Vec<(String, Vec<u8>)>) {
+        let mut response = ClientStateListResponse::new();
+        response.set_status(ClientStateListResponse_Status::OK);
+        response.set_entries(protobuf::RepeatedField::from_vec(entries.into_iter()
+            .map(|(address, data)| {
+                let mut entry = ClientStateListResponse_Entry::new();
+                entry.set_address(address);
+                entry.set_data(data);
+                entry
+            })
+            .collect()));
+        self.reply_with(Message_MessageType::CLIENT_STATE_LIST_RESPONSE, &response);
+    }
+
+    pub fn reply_with_wrong_type(&self) {
+        self.replies.lock().unwrap().push_back((Message_MessageType::PING_RESPONSE, Vec::new()));
+    }
+}
+
+impl Drop for FakeValidator {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
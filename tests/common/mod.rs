@@ -0,0 +1,44 @@
+#![allow(dead_code)]
+
+pub mod fake_validator;
+
+use sawtooth_sdk::signing;
+use sawtooth_sdk::messages::batch::Batch;
+use sawtooth_alica_payload::{payloads, TransactionFamily};
+use alica_messages_client::sawtooth::{Client, BatchFactory, TransactionFactory};
+use alica_messages_client::sawtooth::factory::GeneralPurposeComponentFactory;
+use alica_messages_client::sawtooth::transport::memory::SimulatedLedger;
+use fake_validator::FakeValidator;
+
+pub fn create_test_signer<'a>() -> signing::Signer<'a> {
+    let context = signing::create_context("secp256k1").expect("No secp256k1 context");
+    let private_key = context.new_random_private_key().expect("Could not create private key");
+    signing::Signer::new_boxed(context, private_key)
+}
+
+pub fn create_test_family() -> TransactionFamily {
+    TransactionFamily::new("alica_messages", &vec!["0.1.0".to_string()])
+}
+
+pub fn create_test_batch() -> Batch {
+    let family = create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let factory = GeneralPurposeComponentFactory::new(&family, &format, create_test_signer());
+    let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000);
+
+    let header = factory.create_transaction_header_for(&payload).expect("Could not create header");
+    let transactions = vec![factory.create_transaction_for(&payload, &header).expect("Could not create transaction")];
+    let batch_header = factory.create_batch_header_for(&transactions).expect("Could not create batch header");
+    factory.create_batch_for(&transactions, &batch_header).expect("Could not create batch")
+}
+
+pub fn memory_client<'a>(family: &'a TransactionFamily, format: &'a dyn payloads::Format) -> Client<'a> {
+    let factory = Box::leak(Box::new(GeneralPurposeComponentFactory::new(family, format, create_test_signer())));
+    Client::with_transport(Box::new(SimulatedLedger::new(family, format)), factory)
+}
+
+pub fn validator_client<'a>(validator: &FakeValidator, family: &'a TransactionFamily, format: &'a dyn payloads::Format)
+                            -> Client<'a> {
+    let factory = Box::leak(Box::new(GeneralPurposeComponentFactory::new(family, format, create_test_signer())));
+    Client::new(validator.url(), factory).expect("Could not connect")
+}
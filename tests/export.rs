@@ -0,0 +1,62 @@
+mod common;
+
+use std::env;
+use std::fs;
+use sawtooth_alica_payload::payloads;
+use serde_json::Value;
+use alica_messages_client::command::{export, SawtoothCommand};
+use alica_messages_client::filter::{AgentIdFilter, TransactionPayloadFilter};
+use alica_messages_client::sawtooth::helper;
+
+#[test]
+fn export_writes_the_filtered_messages_and_a_manifest_of_the_chain_head() {
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::memory_client(&family, &format);
+    let first = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "first, with comma".as_bytes(), 1605000000);
+    let second = payloads::TransactionPayload::new("agent2", "AlicaEngineInfo", "second".as_bytes(), 1605000001);
+    client.create_batch(&[&first]).expect("Batch was rejected");
+    client.create_batch(&[&second]).expect("Batch was rejected");
+    let directory = env::temp_dir().join(format!("export-{}", uuid::Uuid::new_v4()));
+    let filters: Vec<Box<dyn TransactionPayloadFilter>> = vec![Box::from(AgentIdFilter::new("agent1"))];
+
+    let result = export::ExportCommand::new(client, &family.calculate_namespace(), &format, filters, &directory)
+        .with_filter_expressions(vec!["agent_id=agent1".to_string()])
+        .execute();
+
+    assert!(result.is_ok());
+    let manifest: Value = serde_json::from_slice(&fs::read(directory.join("manifest.json")).unwrap()).unwrap();
+    let json_lines = fs::read(directory.join("messages.jsonl")).unwrap();
+    let csv = fs::read_to_string(directory.join("messages.csv")).unwrap();
+    fs::remove_dir_all(&directory).expect("Could not remove export directory");
+    assert_eq!(manifest["chain_head"]["block_num"], 1);
+    assert_eq!(manifest["counts"]["entries"], 2);
+    assert_eq!(manifest["counts"]["exported_messages"], 1);
+    assert_eq!(manifest["filters"][0], "agent_id=agent1");
+    assert_eq!(manifest["files"][0]["sha512"], helper::calculate_checksum(&json_lines));
+    assert_eq!(String::from_utf8(json_lines).unwrap().lines().count(), 1);
+    assert!(csv.contains("\"first, with comma\""));
+}
+
+#[test]
+fn export_writes_the_messages_of_every_page_of_the_state() {
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::memory_client(&family, &format);
+    let payloads: Vec<payloads::TransactionPayload> = (0..150)
+        .map(|timestamp| payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), timestamp))
+        .collect();
+    client.create_batch(&payloads.iter().collect::<Vec<_>>()).expect("Batch was rejected");
+    let directory = env::temp_dir().join(format!("export-{}", uuid::Uuid::new_v4()));
+
+    let result = export::ExportCommand::new(client, &family.calculate_namespace(), &format, Vec::new(), &directory)
+        .execute();
+
+    assert!(result.is_ok());
+    let manifest: Value = serde_json::from_slice(&fs::read(directory.join("manifest.json")).unwrap()).unwrap();
+    let json_lines = fs::read_to_string(directory.join("messages.jsonl")).unwrap();
+    fs::remove_dir_all(&directory).expect("Could not remove export directory");
+    assert_eq!(manifest["counts"]["entries"], 150);
+    assert_eq!(manifest["counts"]["exported_messages"], 150);
+    assert_eq!(json_lines.lines().count(), 150);
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use sawtooth_sdk::messages::transaction::TransactionHeader;
+use sawtooth_alica_payload::TransactionFamily;
+use sawtooth_alica_payload::payloads::{self, Format};
+use alica_messages_client::sawtooth::{BatchFactory, TransactionFactory, TransactionOptions};
+use alica_messages_client::sawtooth::factory::{GeneralPurposeComponentFactory, NonceStrategy};
+use alica_messages_client::sawtooth::helper;
+
+#[test]
+fn transaction_headers_reference_the_payload_and_its_state_address() {
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let factory = GeneralPurposeComponentFactory::new(&family, &format, common::create_test_signer());
+    let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000);
+
+    let header = factory.create_transaction_header_for(&payload).expect("Could not create header");
+
+    let address = family.calculate_state_address_for(&payload);
+    assert_eq!(header.get_family_name(), "alica_messages");
+    assert_eq!(header.get_family_version(), "0.1.0");
+    assert_eq!(header.get_inputs(), header.get_outputs());
+    assert_eq!(header.get_outputs(), &[address]);
+    let serialized_payload = format.serialize(&payload).ok().expect("Could not serialize payload");
+    assert_eq!(header.get_payload_sha512(), helper::calculate_checksum(&serialized_payload));
+    assert_eq!(header.get_signer_public_key(), header.get_batcher_public_key());
+}
+
+#[test]
+fn transactions_and_batches_carry_valid_signatures() {
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let factory = GeneralPurposeComponentFactory::new(&family, &format, common::create_test_signer());
+    let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000);
+
+    let header = factory.create_transaction_header_for(&payload).expect("Could not create header");
+    let transaction = factory.create_transaction_for(&payload, &header).expect("Could not create transaction");
+    let transactions = vec![transaction];
+    let batch_header = factory.create_batch_header_for(&transactions).expect("Could not create batch header");
+    let batch = factory.create_batch_for(&transactions, &batch_header).expect("Could not create batch");
+
+    let header = protobuf::parse_from_bytes::<TransactionHeader>(transactions[0].get_header()).unwrap();
+    assert!(helper::verify_signature(transactions[0].get_header_signature(), transactions[0].get_header(),
+                                     header.get_signer_public_key()));
+    assert!(helper::verify_signature(batch.get_header_signature(), batch.get_header(),
+                                     batch_header.get_signer_public_key()));
+    assert_eq!(batch_header.get_transaction_ids(), &[transactions[0].get_header_signature().to_string()]);
+}
+
+#[test]
+fn transaction_headers_carry_the_selected_family_version() {
+    let family = TransactionFamily::new("alica_messages", &vec!["0.1.0".to_string(), "0.2.0".to_string()]);
+    let format = payloads::pipe_separated::Format::default();
+    let factory = GeneralPurposeComponentFactory::new(&family, &format, common::create_test_signer())
+        .with_family_version("0.1.0");
+    let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000);
+
+    let header = factory.create_transaction_header_for(&payload).expect("Could not create header");
+
+    assert_eq!(header.get_family_version(), "0.1.0");
+}
+
+#[test]
+fn transaction_headers_declare_additional_addresses_and_dependencies() {
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let factory = GeneralPurposeComponentFactory::new(&family, &format, common::create_test_signer());
+    let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000);
+    let address = family.calculate_state_address_for(&payload);
+    let other_address = family.calculate_namespace() + &"0".repeat(64);
+    let options = TransactionOptions {
+        inputs: vec![other_address.clone(), address.clone()],
+        outputs: Vec::new(),
+        dependencies: vec!["a".repeat(128)],
+        idempotency_key: Some("import-1".to_string())
+    };
+
+    let header = factory.create_transaction_header_with(&payload, &options).expect("Could not create header");
+
+    assert_eq!(header.get_inputs(), &[address.clone(), other_address]);
+    assert_eq!(header.get_outputs(), &[address]);
+    assert_eq!(header.get_dependencies(), &["a".repeat(128)]);
+    assert_eq!(header.get_nonce(), helper::calculate_checksum(&"import-1".as_bytes()));
+}
+
+#[test]
+fn derived_nonces_recreate_identical_transactions_for_identical_messages() {
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let factory = GeneralPurposeComponentFactory::new(&family, &format, common::create_test_signer())
+        .with_nonce_strategy(NonceStrategy::Derived);
+    let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000);
+    let other_payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000001);
+
+    let header = factory.create_transaction_header_for(&payload).expect("Could not create header");
+    let repeated_header = factory.create_transaction_header_for(&payload).expect("Could not create header");
+    let other_header = factory.create_transaction_header_for(&other_payload).expect("Could not create header");
+
+    assert_eq!(header.get_nonce(), repeated_header.get_nonce());
+    assert_ne!(header.get_nonce(), other_header.get_nonce());
+    let transaction = factory.create_transaction_for(&payload, &header).expect("Could not create transaction");
+    let repeated_transaction = factory.create_transaction_for(&payload, &repeated_header).expect("Could not create transaction");
+    assert_eq!(transaction.get_header_signature(), repeated_transaction.get_header_signature());
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use sawtooth_sdk::messages::validator::Message_MessageType;
+use sawtooth_sdk::messages::block::{Block, BlockHeader};
+use sawtooth_sdk::messages::client_status::{ClientStatusGetResponse, ClientStatusGetResponse_Status};
+use sawtooth_sdk::messages::client_peers::{ClientPeersGetResponse, ClientPeersGetResponse_Status};
+use sawtooth_sdk::messages::client_block::{ClientBlockListResponse, ClientBlockListResponse_Status};
+use sawtooth_sdk::messages::client_state::{ClientStateGetRequest, ClientStateGetResponse, ClientStateGetResponse_Status};
+use sawtooth_sdk::messages::setting::{Setting, Setting_Entry};
+use sawtooth_alica_payload::payloads;
+use protobuf::Message;
+use alica_messages_client::command::{network, SawtoothCommand};
+use alica_messages_client::sawtooth::helper;
+use common::fake_validator::FakeValidator;
+
+fn reply_with_running_network(validator: &FakeValidator) {
+    let mut status = ClientStatusGetResponse::new();
+    status.set_status(ClientStatusGetResponse_Status::OK);
+    status.set_endpoint("tcp://validator:8800".to_string());
+    validator.reply_with(Message_MessageType::CLIENT_STATUS_GET_RESPONSE, &status);
+
+    let mut peers = ClientPeersGetResponse::new();
+    peers.set_status(ClientPeersGetResponse_Status::OK);
+    peers.set_peers(protobuf::RepeatedField::from_vec(vec!["tcp://peer:8800".to_string()]));
+    validator.reply_with(Message_MessageType::CLIENT_PEERS_GET_RESPONSE, &peers);
+
+    let mut header = BlockHeader::new();
+    header.set_block_num(42);
+    let mut block = Block::new();
+    block.set_header(header.write_to_bytes().unwrap());
+    block.set_header_signature("head".to_string());
+    let mut blocks = ClientBlockListResponse::new();
+    blocks.set_status(ClientBlockListResponse_Status::OK);
+    blocks.set_blocks(protobuf::RepeatedField::from_vec(vec![block]));
+    validator.reply_with(Message_MessageType::CLIENT_BLOCK_LIST_RESPONSE, &blocks);
+}
+
+fn reply_with_transaction_families(validator: &FakeValidator, families: &str) {
+    let mut entry = Setting_Entry::new();
+    entry.set_key("sawtooth.validator.transaction_families".to_string());
+    entry.set_value(families.to_string());
+    let mut setting = Setting::new();
+    setting.set_entries(protobuf::RepeatedField::from_vec(vec![entry]));
+    let mut response = ClientStateGetResponse::new();
+    response.set_status(ClientStateGetResponse_Status::OK);
+    response.set_value(setting.write_to_bytes().unwrap());
+    validator.reply_with(Message_MessageType::CLIENT_STATE_GET_RESPONSE, &response);
+}
+
+#[test]
+fn status_reads_the_transaction_families_setting() {
+    let validator = FakeValidator::start();
+    reply_with_running_network(&validator);
+    reply_with_transaction_families(&validator, r#"[{"family": "alica_messages", "version": "0.1.0"}]"#);
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::validator_client(&validator, &family, &format);
+
+    let result = network::StatusCommand::new(client, "alica_messages", "0.1.0").execute();
+
+    assert!(result.is_ok());
+    let requests = validator.requests();
+    assert_eq!(requests.len(), 4);
+    let state_request = protobuf::parse_from_bytes::<ClientStateGetRequest>(requests[3].get_content()).unwrap();
+    assert_eq!(state_request.get_address(), helper::setting_address("sawtooth.validator.transaction_families"));
+}
+
+#[test]
+fn status_fails_if_the_family_is_not_an_accepted_transaction_family() {
+    let validator = FakeValidator::start();
+    reply_with_running_network(&validator);
+    reply_with_transaction_families(&validator, r#"[{"family": "intkey", "version": "1.0"}]"#);
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::validator_client(&validator, &family, &format);
+
+    let result = network::StatusCommand::new(client, "alica_messages", "0.1.0").execute();
+
+    assert!(result.is_err());
+}
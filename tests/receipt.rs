@@ -0,0 +1,58 @@
+mod common;
+
+use sawtooth_sdk::messages::validator::Message_MessageType;
+use sawtooth_sdk::messages::client_receipt::{ClientReceiptGetRequest, ClientReceiptGetResponse, ClientReceiptGetResponse_Status};
+use sawtooth_alica_payload::payloads;
+use alica_messages_client::command::{receipt, SawtoothCommand};
+use common::fake_validator::FakeValidator;
+
+#[test]
+fn show_requests_the_receipts_of_all_given_transactions() {
+    let validator = FakeValidator::start();
+    let mut response = ClientReceiptGetResponse::new();
+    response.set_status(ClientReceiptGetResponse_Status::OK);
+    validator.reply_with(Message_MessageType::CLIENT_RECEIPT_GET_RESPONSE, &response);
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::validator_client(&validator, &family, &format);
+    let transaction_ids = vec!["transaction1".to_string(), "transaction2".to_string()];
+
+    let result = receipt::ShowCommand::new(client, transaction_ids.clone(), &format).execute();
+
+    assert!(result.is_ok());
+    let requests = validator.requests();
+    assert_eq!(requests[0].get_message_type(), Message_MessageType::CLIENT_RECEIPT_GET_REQUEST);
+    let request = protobuf::parse_from_bytes::<ClientReceiptGetRequest>(requests[0].get_content()).unwrap();
+    assert_eq!(request.get_transaction_ids(), transaction_ids.as_slice());
+}
+
+#[test]
+fn show_fails_for_transactions_that_are_not_committed() {
+    let validator = FakeValidator::start();
+    let mut response = ClientReceiptGetResponse::new();
+    response.set_status(ClientReceiptGetResponse_Status::NO_RESOURCE);
+    validator.reply_with(Message_MessageType::CLIENT_RECEIPT_GET_RESPONSE, &response);
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::validator_client(&validator, &family, &format);
+
+    let result = receipt::ShowCommand::new(client, vec!["transaction1".to_string()], &format).execute();
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn simulated_ledger_records_the_state_change_of_every_applied_transaction() {
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::memory_client(&family, &format);
+    let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000);
+    let options = Default::default();
+    let transaction_ids = client.create_batch_with_options(&[(&payload, &options)]).expect("Batch was rejected");
+
+    let receipts = client.get_receipts(&transaction_ids).expect("Could not get receipts");
+
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].get_transaction_id(), transaction_ids[0]);
+    assert_eq!(receipts[0].get_state_changes()[0].get_address(), family.calculate_state_address_for(&payload));
+}
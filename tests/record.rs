@@ -0,0 +1,140 @@
+mod common;
+
+use std::collections::VecDeque;
+use std::env;
+use std::fs;
+use std::thread;
+use std::time::Duration;
+use sawtooth_alica_payload::payloads::{self, Format};
+use sawtooth_sdk::messages::validator::Message_MessageType;
+use sawtooth_sdk::messages::client_batch_submit::{ClientBatchSubmitRequest, ClientBatchSubmitResponse_Status};
+use alica_messages_client::alica::capnzero::Subscriber;
+use alica_messages_client::command::{record, SawtoothCommand};
+use alica_messages_client::format::json;
+use alica_messages_client::validation::{PayloadValidator, ReservedCharacterValidator};
+use common::fake_validator::FakeValidator;
+
+struct ScriptedSubscriber {
+    messages: VecDeque<(String, Vec<u8>)>
+}
+
+impl ScriptedSubscriber {
+    fn new(messages: Vec<(&str, Vec<u8>)>) -> Self {
+        ScriptedSubscriber {
+            messages: messages.into_iter().map(|(topic, message)| (topic.to_string(), message)).collect()
+        }
+    }
+}
+
+impl Subscriber for ScriptedSubscriber {
+    fn receive(&mut self, timeout: Duration) -> Result<Option<(String, Vec<u8>)>, zmq::Error> {
+        let message = self.messages.pop_front();
+        if message.is_none() {
+            thread::sleep(timeout);
+        }
+        Ok(message)
+    }
+}
+
+fn role_switch_message() -> Vec<u8> {
+    let mut message = Vec::new();
+    message.extend_from_slice(&[0, 0, 0, 0, 6, 0, 0, 0]);
+    message.extend_from_slice(&[0, 0, 0, 0, 1, 0, 1, 0]);
+    message.extend_from_slice(&42i64.to_le_bytes());
+    message.extend_from_slice(&[0, 0, 0, 0, 1, 0, 1, 0]);
+    message.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 0]);
+    message.extend_from_slice(&[1, 0, 0, 0, 2 << 3 | 2, 0, 0, 0]);
+    message.extend_from_slice(&[0xab, 0xcd, 0, 0, 0, 0, 0, 0]);
+    message
+}
+
+#[test]
+fn record_submits_messages_with_their_sender_and_drops_messages_without_one() {
+    let family = common::create_test_family();
+    // Cap'n Proto messages are binary, which the pipe separated format can not hold.
+    let format = json::Format::default();
+    let client = common::memory_client(&family, &format);
+    let subscriber = ScriptedSubscriber::new(vec![
+        ("RoleSwitch", role_switch_message()),
+        ("engine", "{\"senderId\": \"unknown\"}".as_bytes().to_vec())
+    ]);
+    let spool_directory = env::temp_dir().join(format!("record-{}", uuid::Uuid::new_v4()));
+
+    let result = record::RecordCommand::new(client.clone(), Box::new(subscriber), Vec::new(), Vec::new(), &spool_directory)
+        .with_topic("AlicaEngineInfo", "engine")
+        .with_flush_interval(Duration::from_millis(10))
+        .with_duration(Duration::from_millis(200))
+        .execute();
+
+    fs::remove_dir_all(&spool_directory).expect("Could not remove spool directory");
+    assert!(result.is_ok());
+    let payloads: Vec<payloads::TransactionPayload> = client.list_state_entries().unwrap().iter()
+        .map(|entry| format.deserialize(entry.get_data()).ok().expect("Could not deserialize payload"))
+        .collect();
+    assert_eq!(payloads.len(), 1);
+    assert_eq!(payloads[0].agent_id, "abcd");
+    assert_eq!(payloads[0].message_type, "RoleSwitch");
+    assert_eq!(payloads[0].message_bytes, role_switch_message());
+}
+
+#[test]
+fn record_drops_invalid_messages_instead_of_the_batch_they_would_end_up_in() {
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::memory_client(&family, &format);
+    let subscriber = ScriptedSubscriber::new(vec![
+        ("engine", "running|stopped".as_bytes().to_vec()),
+        ("engine", "running".as_bytes().to_vec())
+    ]);
+    let validators: Vec<Box<dyn PayloadValidator>> = vec![Box::from(ReservedCharacterValidator::new("pipe-separated", &['|']))];
+    let spool_directory = env::temp_dir().join(format!("record-{}", uuid::Uuid::new_v4()));
+
+    let result = record::RecordCommand::new(client.clone(), Box::new(subscriber), Vec::new(), validators, &spool_directory)
+        .with_topic("AlicaEngineInfo", "engine")
+        .with_agent_id("robot1")
+        .with_flush_interval(Duration::from_millis(10))
+        .with_duration(Duration::from_millis(200))
+        .execute();
+
+    fs::remove_dir_all(&spool_directory).expect("Could not remove spool directory");
+    assert!(result.is_ok());
+    let payloads: Vec<payloads::TransactionPayload> = client.list_state_entries().unwrap().iter()
+        .map(|entry| format.deserialize(entry.get_data()).ok().expect("Could not deserialize payload"))
+        .collect();
+    assert_eq!(payloads.len(), 1);
+    assert_eq!(payloads[0].message_bytes, "running".as_bytes());
+}
+
+#[test]
+fn record_keeps_messages_buffered_while_the_queue_of_the_validator_is_full() {
+    let validator = FakeValidator::start();
+    validator.reply_with_batch_status(ClientBatchSubmitResponse_Status::QUEUE_FULL);
+    validator.reply_with_batch_status(ClientBatchSubmitResponse_Status::OK);
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::validator_client(&validator, &family, &format);
+    let subscriber = ScriptedSubscriber::new(vec![("engine", "running".as_bytes().to_vec())]);
+    let spool_directory = env::temp_dir().join(format!("record-{}", uuid::Uuid::new_v4()));
+
+    let result = record::RecordCommand::new(client, Box::new(subscriber), Vec::new(), Vec::new(), &spool_directory)
+        .with_topic("AlicaEngineInfo", "engine")
+        .with_agent_id("robot1")
+        .with_flush_interval(Duration::from_millis(10))
+        .with_duration(Duration::from_millis(500))
+        .execute();
+
+    let spooled_messages = fs::read(spool_directory.join("messages")).expect("Spool was not written");
+    fs::remove_dir_all(&spool_directory).expect("Could not remove spool directory");
+    assert!(result.is_ok());
+    assert!(spooled_messages.is_empty());
+    let requests = validator.requests();
+    assert_eq!(requests.len(), 2);
+    for request in &requests {
+        assert_eq!(request.get_message_type(), Message_MessageType::CLIENT_BATCH_SUBMIT_REQUEST);
+        let request = protobuf::parse_from_bytes::<ClientBatchSubmitRequest>(request.get_content()).unwrap();
+        let payload = format.deserialize(request.get_batches()[0].get_transactions()[0].get_payload())
+            .ok().expect("Could not deserialize payload");
+        assert_eq!(payload.agent_id, "robot1");
+        assert_eq!(payload.message_type, "AlicaEngineInfo");
+    }
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::rc::Rc;
+use std::time::Duration;
+use sawtooth_alica_payload::payloads;
+use alica_messages_client::alica::capnzero::Publisher;
+use alica_messages_client::command::{export, replay, SawtoothCommand};
+
+struct RecordingPublisher {
+    published: Rc<RefCell<Vec<(String, String, Vec<u8>)>>>
+}
+
+impl Publisher for RecordingPublisher {
+    fn publish(&mut self, endpoint: &str, topic: &str, message: &[u8]) -> Result<(), zmq::Error> {
+        self.published.borrow_mut().push((endpoint.to_string(), topic.to_string(), message.to_vec()));
+        Ok(())
+    }
+}
+
+#[test]
+fn replay_publishes_the_messages_of_an_export_ordered_by_timestamp() {
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::memory_client(&family, &format);
+    let later = payloads::TransactionPayload::new("agent1", "SyncTalk", &[0, 1, 2], 20);
+    let earlier = payloads::TransactionPayload::new("agent2", "AlicaEngineInfo", "engine".as_bytes(), 10);
+    client.create_batch(&[&later]).expect("Batch was rejected");
+    client.create_batch(&[&earlier]).expect("Batch was rejected");
+    let directory = env::temp_dir().join(format!("replay-{}", uuid::Uuid::new_v4()));
+    export::ExportCommand::new(client, &family.calculate_namespace(), &format, Vec::new(), &directory)
+        .execute().expect("Export failed");
+    let published = Rc::new(RefCell::new(Vec::new()));
+    let routes = replay::Routes::new(Some("tcp://*:5555")).with_topic("SyncTalk", "sync");
+
+    let result = replay::ReplayCommand::new(replay::ReplaySource::Export(directory.join("messages.jsonl")), Vec::new(),
+                                            routes, Box::new(RecordingPublisher { published: published.clone() }))
+        .execute();
+
+    fs::remove_dir_all(&directory).expect("Could not remove export directory");
+    assert!(result.is_ok());
+    assert_eq!(*published.borrow(), vec![
+        ("tcp://*:5555".to_string(), "AlicaEngineInfo".to_string(), "engine".as_bytes().to_vec()),
+        ("tcp://*:5555".to_string(), "sync".to_string(), vec![0, 1, 2])
+    ]);
+}
+
+#[test]
+fn replay_fails_instead_of_looping_if_no_message_has_an_endpoint() {
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::memory_client(&family, &format);
+    let message = payloads::TransactionPayload::new("agent1", "SyncTalk", &[0, 1, 2], 10);
+    client.create_batch(&[&message]).expect("Batch was rejected");
+    let published = Rc::new(RefCell::new(Vec::new()));
+    let routes = replay::Routes::new(None).with_endpoint("AlicaEngineInfo", "tcp://*:5555");
+
+    let result = replay::ReplayCommand::new(replay::ReplaySource::Network(client, family.calculate_namespace(), &format),
+                                            Vec::new(), routes, Box::new(RecordingPublisher { published: published.clone() }))
+        .with_join_delay(Duration::from_millis(0))
+        .with_loop()
+        .execute();
+
+    assert!(result.is_err());
+    assert!(published.borrow().is_empty());
+}
@@ -0,0 +1,199 @@
+mod common;
+
+use sawtooth_sdk::messages::validator::Message_MessageType;
+use sawtooth_sdk::messages::block::{Block, BlockHeader};
+use sawtooth_sdk::messages::client_block::{ClientBlockGetByNumRequest, ClientBlockGetResponse, ClientBlockGetResponse_Status};
+use sawtooth_sdk::messages::client_state::ClientStateListRequest;
+use protobuf::Message;
+use sawtooth_alica_payload::payloads::{self, Format};
+use alica_messages_client::command::{state, SawtoothCommand};
+use alica_messages_client::filter::{AgentIdFilter, TransactionPayloadFilter};
+use common::fake_validator::FakeValidator;
+
+#[test]
+fn list_requests_the_state_entries_of_the_validator() {
+    let validator = FakeValidator::start();
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000);
+    let serialized_payload = format.serialize(&payload).ok().expect("Could not serialize payload");
+    validator.reply_with_state_entries(vec![(family.calculate_state_address_for(&payload), serialized_payload)]);
+    let client = common::validator_client(&validator, &family, &format);
+    let filters: Vec<Box<dyn TransactionPayloadFilter>> = vec![Box::from(AgentIdFilter::new("agent1"))];
+
+    let result = state::ListCommand::new(client, &family.calculate_namespace(), &format, filters).execute();
+
+    assert!(result.is_ok());
+    let requests = validator.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].get_message_type(), Message_MessageType::CLIENT_STATE_LIST_REQUEST);
+}
+
+#[test]
+fn list_ignores_entries_outside_of_the_family_namespace() {
+    let validator = FakeValidator::start();
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    validator.reply_with_state_entries(vec![("000000".repeat(11) + "0000", "not a payload".as_bytes().to_vec())]);
+    let client = common::validator_client(&validator, &family, &format);
+
+    let result = state::ListCommand::new(client, &family.calculate_namespace(), &format, Vec::new()).execute();
+
+    assert!(result.is_ok());
+}
+
+#[test]
+fn list_fails_for_undecodable_entries_in_the_family_namespace() {
+    let validator = FakeValidator::start();
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    validator.reply_with_state_entries(vec![(family.calculate_namespace() + &"0".repeat(64), Vec::new())]);
+    let client = common::validator_client(&validator, &family, &format);
+
+    let result = state::ListCommand::new(client, &family.calculate_namespace(), &format, Vec::new()).execute();
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn list_fails_on_a_wrong_response_type() {
+    let validator = FakeValidator::start();
+    validator.reply_with_wrong_type();
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::validator_client(&validator, &family, &format);
+
+    let result = state::ListCommand::new(client, &family.calculate_namespace(), &format, Vec::new()).execute();
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn list_with_integrity_check_accepts_entries_at_their_derived_address() {
+    let validator = FakeValidator::start();
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000);
+    let serialized_payload = format.serialize(&payload).ok().expect("Could not serialize payload");
+    validator.reply_with_state_entries(vec![(family.calculate_state_address_for(&payload), serialized_payload)]);
+    let client = common::validator_client(&validator, &family, &format);
+
+    let result = state::ListCommand::new(client, &family.calculate_namespace(), &format, Vec::new())
+        .with_integrity_check(&family)
+        .execute();
+
+    assert!(result.is_ok());
+}
+
+#[test]
+fn list_with_integrity_check_fails_for_entries_at_foreign_addresses() {
+    let validator = FakeValidator::start();
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000);
+    let other_payload = payloads::TransactionPayload::new("agent2", "AlicaEngineInfo", "message".as_bytes(), 1605000000);
+    let serialized_payload = format.serialize(&payload).ok().expect("Could not serialize payload");
+    validator.reply_with_state_entries(vec![(family.calculate_state_address_for(&other_payload), serialized_payload)]);
+    let client = common::validator_client(&validator, &family, &format);
+
+    let result = state::ListCommand::new(client, &family.calculate_namespace(), &format, Vec::new())
+        .with_integrity_check(&family)
+        .execute();
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn list_at_block_requests_the_state_at_the_state_root_of_the_block() {
+    let validator = FakeValidator::start();
+    let mut header = BlockHeader::new();
+    header.set_block_num(3);
+    header.set_state_root_hash("root3".to_string());
+    let mut block = Block::new();
+    block.set_header(header.write_to_bytes().expect("Could not serialize block header"));
+    let mut response = ClientBlockGetResponse::new();
+    response.set_status(ClientBlockGetResponse_Status::OK);
+    response.set_block(block);
+    validator.reply_with(Message_MessageType::CLIENT_BLOCK_GET_RESPONSE, &response);
+    validator.reply_with_state_entries(Vec::new());
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::validator_client(&validator, &family, &format);
+
+    let result = state::ListCommand::new(client, &family.calculate_namespace(), &format, Vec::new())
+        .with_block("3")
+        .execute();
+
+    assert!(result.is_ok());
+    let requests = validator.requests();
+    assert_eq!(requests[0].get_message_type(), Message_MessageType::CLIENT_BLOCK_GET_BY_NUM_REQUEST);
+    let block_request = protobuf::parse_from_bytes::<ClientBlockGetByNumRequest>(requests[0].get_content()).unwrap();
+    assert_eq!(block_request.get_block_num(), 3);
+    let state_request = protobuf::parse_from_bytes::<ClientStateListRequest>(requests[1].get_content()).unwrap();
+    assert_eq!(state_request.get_state_root(), "root3");
+}
+
+#[test]
+fn get_at_block_reads_the_state_as_it_was_after_the_block() {
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::memory_client(&family, &format);
+    let first = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "first".as_bytes(), 1605000000);
+    let second = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "second".as_bytes(), 1605000001);
+    client.create_batch(&[&first]).expect("Batch was rejected");
+    client.create_batch(&[&second]).expect("Batch was rejected");
+    let first_address = family.calculate_state_address_for(&first);
+    let second_address = family.calculate_state_address_for(&second);
+
+    let current = state::GetCommand::new(client.clone(), &second_address, &format).read_entry();
+    let after_first = state::GetCommand::new(client.clone(), &first_address, &format).with_block("0").read_entry();
+    let before_second = state::GetCommand::new(client.clone(), &second_address, &format).with_block("0").read_entry();
+
+    assert_eq!(current.expect("Entry was not read").message_bytes, "second".as_bytes());
+    assert_eq!(after_first.expect("Entry was not read").message_bytes, "first".as_bytes());
+    assert!(before_second.is_err());
+}
+
+#[test]
+fn diff_lists_the_entries_committed_between_two_blocks() {
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::memory_client(&family, &format);
+    let first = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "first".as_bytes(), 1605000000);
+    let second = payloads::TransactionPayload::new("agent2", "AlicaEngineInfo", "second".as_bytes(), 1605000001);
+    client.create_batch(&[&first]).expect("Batch was rejected");
+    client.create_batch(&[&second]).expect("Batch was rejected");
+
+    let filters: Vec<Box<dyn TransactionPayloadFilter>> = vec![Box::from(AgentIdFilter::new("agent1"))];
+
+    let changes = state::DiffCommand::new(client.clone(), &family.calculate_namespace(), &format, Vec::new(), "0", "1")
+        .changes().expect("Diff failed");
+    let filtered_changes = state::DiffCommand::new(client.clone(), &family.calculate_namespace(), &format, filters, "0", "1")
+        .changes().expect("Diff failed");
+    let unknown_block = state::DiffCommand::new(client, &family.calculate_namespace(), &format, Vec::new(), "0", "2")
+        .execute();
+
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].0, family.calculate_state_address_for(&second));
+    assert_eq!(changes[0].1.name(), "added");
+    assert!(filtered_changes.is_empty());
+    assert!(unknown_block.is_err());
+}
+
+#[test]
+fn diff_compares_the_entries_of_every_page_of_both_blocks() {
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::memory_client(&family, &format);
+    let payloads: Vec<payloads::TransactionPayload> = (0..240)
+        .map(|timestamp| payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), timestamp))
+        .collect();
+    client.create_batch(&payloads[..120].iter().collect::<Vec<_>>()).expect("Batch was rejected");
+    client.create_batch(&payloads[120..].iter().collect::<Vec<_>>()).expect("Batch was rejected");
+
+    let changes = state::DiffCommand::new(client, &family.calculate_namespace(), &format, Vec::new(), "0", "1")
+        .changes().expect("Diff failed");
+
+    assert_eq!(changes.len(), 120);
+    assert!(changes.iter().all(|(_, change)| change.name() == "added"));
+}
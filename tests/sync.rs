@@ -0,0 +1,69 @@
+mod common;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use sawtooth_sdk::messages::validator::Message_MessageType;
+use sawtooth_sdk::messages::block::Block;
+use sawtooth_sdk::messages::client_block::{ClientBlockListResponse, ClientBlockListResponse_Status};
+use sawtooth_alica_payload::payloads;
+use alica_messages_client::command::{sync, SawtoothCommand};
+use alica_messages_client::sawtooth::Client;
+use alica_messages_client::sawtooth::factory::GeneralPurposeComponentFactory;
+use alica_messages_client::sawtooth::transport::memory::SimulatedLedger;
+use alica_messages_client::sawtooth::transport::mirror::{Mirror, MirroredBlock};
+use common::fake_validator::FakeValidator;
+
+fn mirror_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("{}-{}.mirror", name, uuid::Uuid::new_v4()))
+}
+
+#[test]
+fn sync_appends_only_the_blocks_committed_since_the_last_sync() {
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let factory = GeneralPurposeComponentFactory::new(&family, &format, common::create_test_signer());
+    let client = Client::with_transport(Box::new(SimulatedLedger::new(&family, &format)), &factory);
+    let path = mirror_path("incremental");
+    let first = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "first".as_bytes(), 1605000000);
+    let second = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "second".as_bytes(), 1605000001);
+
+    client.create_batch(&[&first]).expect("Batch was rejected");
+    sync::SyncCommand::new(client.clone(), Mirror::open(&path).unwrap(), &family.name, &family.calculate_namespace())
+        .execute().expect("First sync failed");
+    client.create_batch(&[&second]).expect("Batch was rejected");
+    sync::SyncCommand::new(client.clone(), Mirror::open(&path).unwrap(), &family.name, &family.calculate_namespace())
+        .execute().expect("Second sync failed");
+
+    let mirror = Mirror::open(&path).expect("Could not load mirror");
+    assert_eq!(mirror.head().map(|head| head.block_num), Some(1));
+    let offline_client = Client::with_transport(Box::new(mirror), &factory);
+    assert_eq!(offline_client.list_transactions().expect("Could not list transactions").len(), 2);
+    let entries = offline_client.list_state_entries().expect("Could not list state");
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().any(|entry| entry.get_address() == family.calculate_state_address_for(&second)));
+    fs::remove_dir_all(path).expect("Could not remove mirror");
+}
+
+#[test]
+fn sync_fails_if_the_mirrored_block_is_no_longer_part_of_the_chain() {
+    let validator = FakeValidator::start();
+    let mut block = Block::new();
+    block.set_header_signature("other".to_string());
+    let mut response = ClientBlockListResponse::new();
+    response.set_status(ClientBlockListResponse_Status::OK);
+    response.set_blocks(protobuf::RepeatedField::from_vec(vec![block]));
+    validator.reply_with(Message_MessageType::CLIENT_BLOCK_LIST_RESPONSE, &response);
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let client = common::validator_client(&validator, &family, &format);
+    let path = mirror_path("forked");
+    let mirror = Mirror::open(&path).unwrap();
+    mirror.append(MirroredBlock { block_num: 0, block_id: "mirrored".to_string() }, &[], &[])
+        .expect("Could not write mirror");
+
+    let result = sync::SyncCommand::new(client, mirror, &family.name, &family.calculate_namespace()).execute();
+
+    fs::remove_dir_all(path).expect("Could not remove mirror");
+    assert!(result.is_err());
+}
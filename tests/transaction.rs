@@ -0,0 +1,91 @@
+mod common;
+
+use sawtooth_sdk::messages::validator::Message_MessageType;
+use sawtooth_sdk::messages::client_transaction::{ClientTransactionListResponse, ClientTransactionListResponse_Status};
+use sawtooth_sdk::messages::transaction::Transaction;
+use sawtooth_alica_payload::TransactionFamily;
+use sawtooth_alica_payload::payloads::{self, Format};
+use alica_messages_client::command::{transaction, SawtoothCommand};
+use alica_messages_client::output::OutputFormat;
+use alica_messages_client::sawtooth::{Client, TransactionFactory};
+use alica_messages_client::sawtooth::factory::GeneralPurposeComponentFactory;
+use common::fake_validator::FakeValidator;
+
+fn create_transaction(factory: &GeneralPurposeComponentFactory, payload: &payloads::TransactionPayload) -> Transaction {
+    let header = factory.create_transaction_header_for(payload).expect("Could not create header");
+    factory.create_transaction_for(payload, &header).expect("Could not create transaction")
+}
+
+fn reply_with_transactions(validator: &FakeValidator, transactions: Vec<Transaction>) {
+    let mut response = ClientTransactionListResponse::new();
+    response.set_status(ClientTransactionListResponse_Status::OK);
+    response.set_transactions(protobuf::RepeatedField::from_vec(transactions));
+    validator.reply_with(Message_MessageType::CLIENT_TRANSACTION_LIST_RESPONSE, &response);
+}
+
+#[test]
+fn list_requests_the_transactions_of_the_validator() {
+    let validator = FakeValidator::start();
+    let family = common::create_test_family();
+    let format = payloads::pipe_separated::Format::default();
+    let factory = GeneralPurposeComponentFactory::new(&family, &format, common::create_test_signer());
+    let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000);
+    reply_with_transactions(&validator, vec![create_transaction(&factory, &payload)]);
+    let client = Client::new(validator.url(), &factory).expect("Could not connect");
+
+    let result = transaction::ListCommand::new(client, &family.name, vec![("0.1.0".to_string(), &format)], Vec::new())
+        .with_output_format(OutputFormat::Json)
+        .execute();
+
+    assert!(result.is_ok());
+    let requests = validator.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].get_message_type(), Message_MessageType::CLIENT_TRANSACTION_LIST_REQUEST);
+}
+
+#[test]
+fn list_skips_transactions_of_other_families() {
+    let validator = FakeValidator::start();
+    let family = common::create_test_family();
+    let other_family = TransactionFamily::new("other_messages", &vec!["0.1.0".to_string()]);
+    let format = payloads::pipe_separated::Format::default();
+    let other_factory = GeneralPurposeComponentFactory::new(&other_family, &format, common::create_test_signer());
+    let mut transaction = create_transaction(&other_factory,
+        &payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000));
+    transaction.set_payload("not a payload".as_bytes().to_vec());
+    reply_with_transactions(&validator, vec![transaction]);
+    let factory = GeneralPurposeComponentFactory::new(&family, &format, common::create_test_signer());
+    let client = Client::new(validator.url(), &factory).expect("Could not connect");
+
+    let result = transaction::ListCommand::new(client, &family.name, vec![("0.1.0".to_string(), &format)], Vec::new())
+        .execute();
+
+    assert!(result.is_ok());
+}
+
+#[test]
+fn list_decodes_transactions_with_the_format_of_their_family_version() {
+    let validator = FakeValidator::start();
+    let family = TransactionFamily::new("alica_messages", &vec!["0.1.0".to_string(), "0.2.0".to_string()]);
+    let pipe_separated = payloads::pipe_separated::Format::default();
+    let json = alica_messages_client::format::json::Format::default();
+    let payload = payloads::TransactionPayload::new("agent1", "AlicaEngineInfo", "message".as_bytes(), 1605000000);
+    let old_factory = GeneralPurposeComponentFactory::new(&family, &pipe_separated, common::create_test_signer())
+        .with_family_version("0.1.0");
+    let new_factory = GeneralPurposeComponentFactory::new(&family, &json, common::create_test_signer());
+    let new_transaction = create_transaction(&new_factory, &payload);
+    reply_with_transactions(&validator, vec![create_transaction(&old_factory, &payload), new_transaction.clone()]);
+    reply_with_transactions(&validator, vec![new_transaction]);
+    let client = Client::new(validator.url(), &new_factory).expect("Could not connect");
+    let version_formats: Vec<(String, &dyn Format)> =
+        vec![("0.1.0".to_string(), &pipe_separated), ("0.2.0".to_string(), &json)];
+
+    let result = transaction::ListCommand::new(client, &family.name, version_formats, Vec::new()).execute();
+    let client = Client::new(validator.url(), &new_factory).expect("Could not connect");
+    let single_format_result = transaction::ListCommand::new(client, &family.name,
+                                                             vec![("0.2.0".to_string(), &pipe_separated)], Vec::new())
+        .execute();
+
+    assert!(result.is_ok());
+    assert!(single_format_result.is_err());
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use sawtooth_sdk::messages::batch::{Batch, BatchList};
+use protobuf::Message;
+use alica_messages_client::command::{verify, SawtoothCommand};
+use alica_messages_client::sawtooth::verification::Violation;
+
+fn write_batch_file(name: &str, batches: Vec<Batch>) -> PathBuf {
+    let mut batch_list = BatchList::new();
+    batch_list.set_batches(protobuf::RepeatedField::from_vec(batches));
+    let path = env::temp_dir().join(format!("{}-{}.batches", name, uuid::Uuid::new_v4()));
+    fs::write(&path, batch_list.write_to_bytes().expect("Could not serialize batch list"))
+        .expect("Could not write batch file");
+    path
+}
+
+#[test]
+fn verify_accepts_unaltered_batches_from_a_batch_file() {
+    let path = write_batch_file("unaltered", vec![common::create_test_batch(), common::create_test_batch()]);
+
+    let command = verify::BatchesCommand::new(verify::BatchSource::File(path.clone()));
+    let violations = command.violations().expect("Could not verify batches");
+    let result = command.execute();
+
+    fs::remove_file(path).expect("Could not remove batch file");
+    assert!(violations.iter().all(|(_, violations)| violations.is_empty()));
+    assert!(result.is_ok());
+}
+
+#[test]
+fn verify_fails_for_batches_with_altered_payloads() {
+    let mut altered_batch = common::create_test_batch();
+    altered_batch.mut_transactions()[0].set_payload("agent1|AlicaEngineInfo|altered|1605000000".as_bytes().to_vec());
+    let transaction_id = altered_batch.get_transactions()[0].get_header_signature().to_string();
+    let path = write_batch_file("altered", vec![common::create_test_batch(), altered_batch]);
+
+    let command = verify::BatchesCommand::new(verify::BatchSource::File(path.clone()));
+    let violations = command.violations().expect("Could not verify batches");
+    let result = command.execute();
+
+    fs::remove_file(path).expect("Could not remove batch file");
+    assert_eq!(violations[0].1, Vec::new());
+    assert_eq!(violations[1].1,
+               vec![Violation::InvalidTransaction(transaction_id, Box::new(Violation::PayloadChecksumMismatch))]);
+    assert!(result.is_err());
+}